@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+
+/// Gets the crafting recipes, mapping two lowercase ingredient names to the item they make
+pub fn default_recipes() -> HashMap<(String, String), String> {
+    [
+        ("silver amulet", "jade scarab", "scarab amulet"),
+    ]
+    .into_iter()
+    .map(|(a, b, result)| ((a.to_string(), b.to_string()), result.to_string()))
+    .collect()
+}
+
+/// Finds the item two ingredients combine into, in either order
+pub fn find_recipe<'a>(recipes: &'a HashMap<(String, String), String>, a: &str, b: &str) -> Option<&'a String> {
+    let (a, b) = (a.to_lowercase(), b.to_lowercase());
+    recipes
+        .get(&(a.clone(), b.clone()))
+        .or_else(|| recipes.get(&(b, a)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recipes_work_in_either_order() {
+        let recipes = default_recipes();
+        assert_eq!(find_recipe(&recipes, "Jade Scarab", "silver amulet"), Some(&"scarab amulet".to_string()));
+        assert_eq!(find_recipe(&recipes, "silver amulet", "jade scarab"), Some(&"scarab amulet".to_string()));
+        assert_eq!(find_recipe(&recipes, "torch", "jade scarab"), None);
+    }
+}
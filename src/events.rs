@@ -0,0 +1,14 @@
+use crate::game::CommandResult;
+use crate::input::Command;
+
+/// Receives notifications about what happens in a game, for frontends such as audio
+///
+/// Every method does nothing by default, so a sink only implements the events it cares about.
+pub trait EventSink {
+    /// Called after the player moves into a room
+    fn on_enter_room(&self, _name: &str) {}
+    /// Called after the player picks up an item
+    fn on_take_item(&self, _item: &str) {}
+    /// Called after every command the game processes, with what came of it
+    fn on_command(&self, _command: &Command, _result: &CommandResult) {}
+}
@@ -1,269 +1,4010 @@
-use std::collections::HashMap;
-use crate::room::{Room, Direction, create_rooms};
-use crate::player::Player;
-use crate::input::Command;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+use std::rc::Rc;
+use serde::{Deserialize, Serialize};
+use crate::room::{Room, Direction, DescribeOptions, create_rooms, link_rooms};
+use crate::player::{Player, DEFAULT_MAX_WEIGHT};
+use crate::input::{Command, InputSource, ParseError, VerbTable, expand_alias, parse_command_with, split_commands};
+use crate::item::ANCIENT_MAP_DIAGRAM;
+use crate::crafting;
+use crate::events::EventSink;
+use crate::messages::{self, Messages};
+use crate::highscores::{self, ScoreEntry};
+use crate::map;
+use crate::rng::Rng;
+use crate::score::{self, ESCAPE_BONUS, MOVE_PENALTY, NARROW_ESCAPE_BONUS};
+use crate::storage::Storage;
+use crate::world::WorldDef;
 
-/// Game state and logic
-#[derive(Clone)]
+/// Percentage chance that searching a trapped room springs the trap in hard mode
+const TRAP_CHANCE: u32 = 50;
+
+/// Number of moves the player gets before the temple collapses
+const DEFAULT_TURN_LIMIT: u32 = 50;
+/// Number of moves on easy difficulty
+const EASY_TURN_LIMIT: u32 = 80;
+/// Number of moves on hard difficulty
+const HARD_TURN_LIMIT: u32 = 30;
+/// Most weight the player can carry on easy difficulty
+const EASY_MAX_WEIGHT: u32 = 15;
+/// Most weight the player can carry on hard difficulty
+const HARD_MAX_WEIGHT: u32 = 8;
+/// Remaining moves below which the player is warned about the collapse
+const COLLAPSE_WARNING_TURNS: u32 = 10;
+/// Moves made before parts of the temple start caving in
+const DEFAULT_CAVE_IN_AFTER: u32 = 30;
+/// Moves made before caving in starts on easy difficulty
+const EASY_CAVE_IN_AFTER: u32 = 50;
+/// Moves made before caving in starts on hard difficulty
+const HARD_CAVE_IN_AFTER: u32 = 15;
+/// Moves between one cave-in and the next
+const CAVE_IN_EVERY: u32 = 5;
+
+/// Number of actions that can be undone
+const MAX_UNDO_HISTORY: usize = 20;
+
+/// Number of rooms remembered in the player's trail of footsteps
+const MAX_PATH_HISTORY: usize = 200;
+
+/// Flag set while the crypt's star tapestry still shows its constellation clue
+const FLAG_STAR_CLUE: &str = "star_clue";
+/// Flag set once the torch has been lit
+const FLAG_TORCH_LIT: &str = "torch_lit";
+/// Flag set once the star tapestry has been burned away
+const FLAG_TAPESTRY_BURNED: &str = "tapestry_burned";
+/// Flag set once the star map has been read, revealing the secret passage
+const FLAG_PASSAGE_FOUND: &str = "passage_found";
+
+/// Items that can be moved around when the temple is shuffled; the golden idol always stays put
+const SHUFFLED_ITEMS: &[&str] = &["torch", "ceremonial dagger", "ancient map"];
+
+/// Name of the room where the golden idol is guarded
+const GUARDIAN_CHAMBER: &str = "Guardian Chamber";
+
+/// Name of the room behind the star map
+const SECRET_PASSAGE: &str = "Secret Passage";
+
+/// How punishing the game is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Difficulty {
+    /// More moves and a bigger pack
+    Easy,
+    /// Searching is always safe
+    Normal,
+    /// Fewer moves, a smaller pack, no hints, and searching trapped rooms may spring their traps
+    Hard,
+}
+
+impl Difficulty {
+    /// Gets the difficulty's display name
+    pub fn name(&self) -> &str {
+        match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        }
+    }
+}
+
+/// Settings that shape a game, derived from its difficulty
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GameConfig {
+    /// The selected difficulty
+    pub difficulty: Difficulty,
+    /// Number of moves the player gets before the temple collapses
+    pub turn_limit: u32,
+    /// Most weight the player can carry
+    pub max_weight: u32,
+    /// Whether the hint command gives suggestions
+    pub hints_enabled: bool,
+    /// Number of moves before unexplored dead ends start caving in, or 0 for never
+    #[serde(default)]
+    pub cave_in_after: u32,
+    /// Number of moves between cave-ins once they have started
+    #[serde(default)]
+    pub cave_in_every: u32,
+}
+
+impl GameConfig {
+    /// Gets the standard settings for a difficulty
+    pub fn for_difficulty(difficulty: Difficulty) -> Self {
+        match difficulty {
+            Difficulty::Easy => Self {
+                difficulty,
+                turn_limit: EASY_TURN_LIMIT,
+                max_weight: EASY_MAX_WEIGHT,
+                hints_enabled: true,
+                cave_in_after: EASY_CAVE_IN_AFTER,
+                cave_in_every: CAVE_IN_EVERY,
+            },
+            Difficulty::Normal => Self {
+                difficulty,
+                turn_limit: DEFAULT_TURN_LIMIT,
+                max_weight: DEFAULT_MAX_WEIGHT,
+                hints_enabled: true,
+                cave_in_after: DEFAULT_CAVE_IN_AFTER,
+                cave_in_every: CAVE_IN_EVERY,
+            },
+            Difficulty::Hard => Self {
+                difficulty,
+                turn_limit: HARD_TURN_LIMIT,
+                max_weight: HARD_MAX_WEIGHT,
+                hints_enabled: false,
+                cave_in_after: HARD_CAVE_IN_AFTER,
+                cave_in_every: CAVE_IN_EVERY,
+            },
+        }
+    }
+}
+
+impl Default for GameConfig {
+    fn default() -> Self {
+        Self::for_difficulty(Difficulty::Normal)
+    }
+}
+
+/// Gets the short names players use for items, mapped to the item's full name
+fn item_aliases() -> HashMap<&'static str, &'static str> {
+    HashMap::from([
+        ("map", "ancient map"),
+        ("idol", "golden idol"),
+        ("dagger", "ceremonial dagger"),
+    ])
+}
+
+/// Resolves a short item name to the item's full name, leaving other names as they are
+pub fn canonicalize_item(input: &str) -> String {
+    let input = input.trim().to_lowercase();
+    match item_aliases().get(input.as_str()) {
+        Some(canonical) => canonical.to_string(),
+        None => input,
+    }
+}
+
+/// The ways the player can escape the temple
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Ending {
+    /// Opening the great doors with the golden idol
+    TrueEscape,
+    /// Squeezing out through the secret passage behind the star map
+    NarrowEscape,
+}
+
+impl Ending {
+    /// Gets how the ending ranks against the others
+    pub fn ranking(&self) -> &str {
+        match self {
+            Ending::TrueEscape => "true escape",
+            Ending::NarrowEscape => "narrow escape",
+        }
+    }
+
+    /// Gets the points awarded for reaching the ending
+    fn bonus(&self) -> i32 {
+        match self {
+            Ending::TrueEscape => ESCAPE_BONUS,
+            Ending::NarrowEscape => NARROW_ESCAPE_BONUS,
+        }
+    }
+}
+
+/// Milestones the player can reach over a game
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Achievement {
+    /// Picking up anything at all
+    FirstItem,
+    /// Lighting the torch in the crypt
+    LitTheCrypt,
+    /// Picking up the golden idol
+    FoundTheIdol,
+    /// Getting out of the temple by any way
+    Escaped,
+}
+
+impl Achievement {
+    /// Gets every achievement, in the order they are listed
+    pub fn all() -> [Achievement; 4] {
+        [Achievement::FirstItem, Achievement::LitTheCrypt, Achievement::FoundTheIdol, Achievement::Escaped]
+    }
+
+    /// Gets the achievement's name
+    pub fn title(&self) -> &str {
+        match self {
+            Achievement::FirstItem => "Finders Keepers",
+            Achievement::LitTheCrypt => "Light in the Dark",
+            Achievement::FoundTheIdol => "Idol Hands",
+            Achievement::Escaped => "Daylight",
+        }
+    }
+
+    /// Gets what the player did to earn the achievement
+    pub fn description(&self) -> &str {
+        match self {
+            Achievement::FirstItem => "Pick up your first item.",
+            Achievement::LitTheCrypt => "Light a torch in the Ancient Crypt.",
+            Achievement::FoundTheIdol => "Take the golden idol.",
+            Achievement::Escaped => "Escape the forgotten temple.",
+        }
+    }
+}
+
+/// The outcome of a command, so callers can react without re-querying the game
+#[derive(Debug, Clone, PartialEq)]
+pub struct CommandResult {
+    /// Message to display to the player
+    pub message: String,
+    /// Whether the player ended up in a different room
+    pub room_changed: bool,
+    /// Whether the game ended
+    pub game_over: bool,
+    /// Whether the player escaped the temple with this command
+    pub won: bool,
+    /// The room the player moved to, if it changed
+    pub new_room: Option<String>,
+    /// The item the player picked up, or the first of them if they took several
+    pub item_taken: Option<String>,
+    /// Every item the player picked up
+    pub items_taken: Vec<String>,
+}
+
+impl fmt::Display for CommandResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Checks a debug setting such as the TEMPLE_DEBUG environment variable, where anything but empty or "0" turns it on
+pub fn debug_enabled(value: Option<&str>) -> bool {
+    value.is_some_and(|value| !value.is_empty() && value != "0")
+}
+
+/// Lists the items in `after` that weren't in `before`, counting repeats such as a second gold coin
+fn new_items(before: &[String], after: &[String]) -> Vec<String> {
+    let mut remaining = before.to_vec();
+    after.iter()
+        .filter(|item| match remaining.iter().position(|held| held == *item) {
+            Some(index) => {
+                remaining.remove(index);
+                false
+            },
+            None => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Turns input the game doesn't understand into its echo around the temple, repeating the last word
+/// and then the tail end of it
+pub fn echo(input: &str) -> String {
+    let last = input.split_whitespace().last().unwrap_or(input);
+    let tail: String = last.chars().skip(last.chars().count() / 2).collect();
+    format!("Your voice echoes: '{}... {}... {}...'", input, last, tail)
+}
+
+/// An action waiting for the player to confirm it
+#[derive(Debug, Clone, PartialEq)]
+enum PendingAction {
+    /// Leaving the game
+    Quit,
+    /// Dropping an item the player needs to escape
+    Drop(String),
+    /// Dropping something to make room for the named item, then taking it
+    Swap(String),
+}
+
+/// The parts of the game an action can change, saved so the action can be undone
+#[derive(Clone, PartialEq)]
+struct GameSnapshot {
+    rooms: HashMap<String, Room>,
+    player: Player,
+    visited: HashSet<String>,
+    path_history: Vec<String>,
+    game_over: bool,
+    won: bool,
+    ending: Option<Ending>,
+    achievements: HashSet<Achievement>,
+    unlocked: HashSet<(String, Direction)>,
+    disarmed: HashSet<(String, String)>,
+    flags: HashSet<String>,
+    message: String,
+    turns: u32,
+    turns_remaining: u32,
+    score: i32,
+    scored_items: HashSet<String>,
+    statue_disarmed: bool,
+    triggered: HashSet<String>,
+    notes: Vec<String>,
+    player_labels: HashMap<String, String>,
+    rng: Rng,
+}
+
+/// Game state and logic. Clones are independent copies, handy for trying out moves, though they share
+/// the event sink
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Game {
     /// All rooms in the game
     rooms: HashMap<String, Room>,
+    /// The rooms as they were when the game began, used when restarting
+    initial_rooms: HashMap<String, Room>,
     /// The player
     player: Player,
+    /// The room the player started in
+    start: String,
+    /// Items the player carries when the game begins
+    #[serde(default)]
+    start_items: Vec<String>,
+    /// Names of the rooms the player has been to
+    visited: HashSet<String>,
+    /// The rooms the player has walked through, in order, starting with the first room
+    #[serde(default)]
+    path_history: Vec<String>,
+    /// Notes the player has attached to rooms, by room name
+    player_labels: HashMap<String, String>,
+    /// Journal entries, written by the player or recorded on discoveries, oldest first
+    notes: Vec<String>,
+    /// Shorthands the player has defined, mapped to the commands they stand for
+    #[serde(default)]
+    aliases: HashMap<String, String>,
     /// Flag indicating if the game is over
     game_over: bool,
+    /// Flag indicating if the player escaped the temple
+    won: bool,
+    /// How the player escaped, once they have
+    ending: Option<Ending>,
+    /// Milestones the player has reached
+    #[serde(default)]
+    achievements: HashSet<Achievement>,
+    /// Locked exits the player has already opened, by room name and direction
+    unlocked: HashSet<(String, Direction)>,
+    /// Traps the player has disarmed, by room name and trap name
+    #[serde(default)]
+    disarmed: HashSet<(String, String)>,
+    /// How far through their dialogue each character is, by character name
+    dialogue_progress: HashMap<String, usize>,
+    /// Story flags describing how the world has changed
+    flags: HashSet<String>,
     /// Game messages to display
     message: String,
+    /// Number of turns that have passed
+    turns: u32,
+    /// Number of moves left before the temple collapses
+    turns_remaining: u32,
+    /// The player's current score
+    score: i32,
+    /// Items the player has already been awarded points for
+    scored_items: HashSet<String>,
+    /// Rooms whose entry events have already fired
+    triggered: HashSet<String>,
+    /// Whether the guardian statue has been jammed so the idol can be taken safely
+    statue_disarmed: bool,
+    /// The settings the game was started with
+    config: GameConfig,
+    /// Random number generator for chance-based events
+    rng: Rng,
+    /// Snapshots taken before recent actions, newest last
+    #[serde(skip)]
+    history: Vec<GameSnapshot>,
+    /// Where finished runs are recorded, if high scores are kept
+    #[serde(skip)]
+    highscores: Option<Rc<RefCell<dyn Storage>>>,
+    /// Action the player has asked for and must confirm
+    #[serde(skip)]
+    pending_action: Option<PendingAction>,
+    /// Listener told about moves and pickups, if a frontend wants them
+    #[serde(skip)]
+    event_sink: Option<Rc<dyn EventSink>>,
+    /// The text used for common responses
+    #[serde(skip)]
+    messages: Messages,
+    /// The verbs the player's input is parsed with
+    #[serde(skip)]
+    verbs: VerbTable,
+    /// Whether input the game doesn't understand is shouted into the temple instead of rejected
+    #[serde(default)]
+    shouting: bool,
+    /// Whether to add diagnostic lines about state changes to the output
+    #[serde(skip)]
+    debug: bool,
+    /// Diagnostic lines gathered while the current command runs
+    #[serde(skip)]
+    debug_lines: Vec<String>,
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Game {
     /// Create a new game with the starting room
     pub fn new() -> Self {
-        let rooms = create_rooms();
-        let player = Player::new("Entrance Hall");
+        Self::with_config(GameConfig::default())
+    }
+
+    /// Create a new game with the given settings
+    pub fn with_config(config: GameConfig) -> Self {
+        Self::with_rng(config, Rng::from_time())
+    }
+
+    /// Create a new game with the given difficulty and a fixed random seed
+    pub fn with_seed(difficulty: Difficulty, seed: u64) -> Self {
+        Self::with_rng(GameConfig::for_difficulty(difficulty), Rng::new(seed))
+    }
+
+    /// Create a new game with the loose items scattered across the temple, the same way for the same seed
+    pub fn new_seeded(seed: u64) -> Self {
+        let mut game = Self::with_seed(Difficulty::Normal, seed);
+        game.shuffle_items();
+        // Restarting keeps the shuffled layout
+        game.initial_rooms = game.rooms.clone();
+        game
+    }
+
+    /// Create a new game with the player starting in the given room, already carrying the given items
+    pub fn new_with_start(room: &str, items: &[&str]) -> Result<Game, String> {
+        let mut game = Self::new();
+        if !game.rooms.contains_key(room) {
+            return Err(format!("There is no room called '{}'.", room));
+        }
+
+        for item in items {
+            // Carried items are no longer lying around the temple
+            let taken = game.rooms.values_mut()
+                .find_map(|room| room.remove_item(item))
+                .unwrap_or_else(|| item.to_string());
+            game.start_items.push(taken);
+        }
+        // Restarting begins the same way
+        game.start = room.to_string();
+        game.initial_rooms = game.rooms.clone();
+        game.reset();
+        Ok(game)
+    }
+
+    /// Create a new game where the temple collapses after the given number of moves
+    pub fn with_turn_limit(turn_limit: u32) -> Self {
+        Self::with_config(GameConfig { turn_limit, ..GameConfig::default() })
+    }
+
+    /// Create a new game from a world definition file instead of the built-in temple
+    pub fn from_world_file(path: &str) -> Result<Game, String> {
+        let world = WorldDef::from_file(path)?;
+        let start = world.start.clone();
+        let rooms = world.into_rooms()?;
+        Ok(Self::with_world(rooms, &start, GameConfig::default(), Rng::from_time()))
+    }
+
+    /// Create a new game using the given settings and random number generator
+    fn with_rng(config: GameConfig, rng: Rng) -> Self {
+        Self::with_world(create_rooms(), "Entrance Hall", config, rng)
+    }
+
+    /// Create a new game in the given world
+    fn with_world(rooms: HashMap<String, Room>, start: &str, config: GameConfig, rng: Rng) -> Self {
+        let mut player = Player::new(start);
+        player.max_weight = config.max_weight;
 
         Game {
+            initial_rooms: rooms.clone(),
             rooms,
             player,
+            start: start.to_string(),
+            start_items: Vec::new(),
+            visited: HashSet::from([start.to_string()]),
+            path_history: vec![start.to_string()],
+            player_labels: HashMap::new(),
+            notes: Vec::new(),
+            aliases: HashMap::new(),
             game_over: false,
+            won: false,
+            ending: None,
+            achievements: HashSet::new(),
+            unlocked: HashSet::new(),
+            disarmed: HashSet::new(),
+            dialogue_progress: HashMap::new(),
+            flags: HashSet::from([FLAG_STAR_CLUE.to_string()]),
             message: String::new(),
+            turns: 0,
+            turns_remaining: config.turn_limit,
+            score: 0,
+            scored_items: HashSet::new(),
+            statue_disarmed: false,
+            triggered: HashSet::new(),
+            config,
+            rng,
+            history: Vec::new(),
+            highscores: None,
+            pending_action: None,
+            event_sink: None,
+            messages: Messages::default(),
+            verbs: VerbTable::default(),
+            shouting: false,
+            debug: false,
+            debug_lines: Vec::new(),
+        }
+    }
+
+    /// Move each shuffleable item to a random room that isn't an exit
+    fn shuffle_items(&mut self) {
+        let mut rooms: Vec<String> = self.rooms.values()
+            .filter(|room| !room.is_exit)
+            .map(|room| room.name.clone())
+            .collect();
+        rooms.sort();
+
+        for item in SHUFFLED_ITEMS {
+            let removed = self.rooms.values_mut().any(|room| room.remove_item(item).is_some());
+            if !removed {
+                continue;
+            }
+            let target = &rooms[self.rng.below(rooms.len())];
+            if let Some(room) = self.rooms.get_mut(target) {
+                room.add_item(item);
+            }
+        }
+    }
+
+    /// Start the game over in the same world, clearing all progress
+    pub fn reset(&mut self) {
+        let highscores = self.highscores.take();
+        let event_sink = self.event_sink.take();
+        let aliases = std::mem::take(&mut self.aliases);
+        let messages = std::mem::take(&mut self.messages);
+        let verbs = std::mem::take(&mut self.verbs);
+        let debug = self.debug;
+        let shouting = self.shouting;
+        let start_items = std::mem::take(&mut self.start_items);
+        *self = Self::with_world(self.initial_rooms.clone(), &self.start, self.config.clone(), self.rng.clone());
+        for item in &start_items {
+            self.player.take_item(item);
+        }
+        self.start_items = start_items;
+        self.highscores = highscores;
+        self.event_sink = event_sink;
+        self.aliases = aliases;
+        self.messages = messages;
+        self.verbs = verbs;
+        self.debug = debug;
+        self.shouting = shouting;
+    }
+
+    /// Tell the given listener about moves and pickups from now on
+    pub fn set_event_sink(&mut self, sink: Rc<dyn EventSink>) {
+        self.event_sink = Some(sink);
+    }
+
+    /// Use the given text for common responses, such as a translation
+    pub fn with_messages(mut self, messages: Messages) -> Self {
+        self.messages = messages;
+        self
+    }
+
+    /// Understand the player's input with the given verbs, such as extra synonyms
+    pub fn with_verbs(mut self, verbs: VerbTable) -> Self {
+        self.verbs = verbs;
+        self
+    }
+
+    /// Add diagnostic lines about moves and events to the output, for checking new maps
+    pub fn with_debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Echo input the game doesn't understand back as a shout, as the 'shout' command does
+    pub fn with_shouting(mut self, shouting: bool) -> Self {
+        self.shouting = shouting;
+        self
+    }
+
+    /// Keep high scores for finished runs in the given storage
+    pub fn with_highscores(mut self, storage: Rc<RefCell<dyn Storage>>) -> Self {
+        self.highscores = Some(storage);
+        self
+    }
+
+    /// Whether finished runs can be recorded in a high-score table
+    pub fn keeps_highscores(&self) -> bool {
+        self.highscores.is_some()
+    }
+
+    /// Record the finished run in the high-score table under the given name
+    pub fn record_score(&self, name: &str) -> Result<(), String> {
+        let Some(storage) = &self.highscores else {
+            return Err("High scores are not being kept.".to_string());
+        };
+        if !self.won {
+            return Err("Only escaped runs make the high-score table.".to_string());
+        }
+
+        let entry = ScoreEntry { name: name.to_string(), score: self.score, moves: self.turns };
+        highscores::save_score(&mut *storage.borrow_mut(), entry)
+    }
+
+    /// Get the high-score table as displayed text
+    pub fn highscores_display(&self) -> String {
+        match &self.highscores {
+            Some(storage) => highscores::format_scores(&highscores::load_scores(&*storage.borrow())),
+            None => "High scores are not being kept.".to_string(),
         }
     }
 
+    /// Save the game under the given slot name
+    pub fn save(&self, storage: &mut dyn Storage, slot: &str) -> Result<(), String> {
+        let data = serde_json::to_vec(self).map_err(|e| format!("Failed to save game: {}", e))?;
+        storage.write(slot, &data)
+    }
+
+    /// Load a game previously saved under the given slot name
+    pub fn load(storage: &dyn Storage, slot: &str) -> Result<Game, String> {
+        let data = storage.read(slot).ok_or_else(|| format!("No saved game named '{}'.", slot))?;
+        serde_json::from_slice(&data).map_err(|e| format!("Failed to load game: {}", e))
+    }
+
     /// Process a command and update the game state
-    pub fn process_command(&mut self, command: Command) -> String {
+    pub fn process_command(&mut self, command: Command) -> CommandResult {
+        let previous_room = self.player.location.clone();
+        let previous_inventory = self.player.inventory.clone();
+        let was_won = self.won;
+        // Answering the full-pack prompt finishes the take that asked it
+        let taking = matches!(command, Command::Take(_) | Command::TakeAll)
+            || matches!(self.pending_action, Some(PendingAction::Swap(_)));
+
+        let before = (command != Command::Undo).then(|| self.snapshot());
+        let was_winnable = self.is_winnable();
+        let turns_before = self.turns;
+        let processed = self.event_sink.is_some().then(|| command.clone());
+
+        let mut message = self.run_command(command);
+        if !self.game_over && self.cave_in_due(turns_before) && self.cave_in() {
+            message.push_str("\n\nA distant rumble — part of the temple has collapsed.");
+        }
+        for line in self.debug_lines.drain(..) {
+            message.push_str(&format!("\n[debug] {}", line));
+        }
+
+        // Remember the state before anything that changed it
+        if let Some(before) = before
+            && before != self.snapshot()
+        {
+            self.history.push(before);
+            if self.history.len() > MAX_UNDO_HISTORY {
+                self.history.remove(0);
+            }
+
+            if was_winnable && !self.game_over && !self.is_winnable() {
+                message.push_str("\n\nAn uneasy feeling settles over you, as if the way out has slipped beyond your reach.");
+            }
+        }
+
+        let room_changed = self.player.location != previous_room;
+        let items_taken = if taking { new_items(&previous_inventory, &self.player.inventory) } else { Vec::new() };
+
+        let result = CommandResult {
+            message,
+            room_changed,
+            game_over: self.game_over,
+            won: self.won && !was_won,
+            new_room: room_changed.then(|| self.player.location.clone()),
+            item_taken: items_taken.first().cloned(),
+            items_taken,
+        };
+        if let (Some(sink), Some(command)) = (&self.event_sink, processed) {
+            sink.on_command(&command, &result);
+        }
+        result
+    }
+
+    /// Run a list of commands without any UI, collecting each result or parse error
+    /// and stopping early if the game ends
+    pub fn run_script(&mut self, commands: &[&str]) -> Vec<String> {
+        let mut lines: Vec<String> = commands.iter().map(|line| line.to_string()).collect();
+        self.run(&mut lines)
+    }
+
+    /// Read and run commands until the input runs out or the game ends, collecting each
+    /// result or parse error
+    pub fn run(&mut self, input: &mut dyn InputSource) -> Vec<String> {
+        let mut outputs = Vec::new();
+        while let Some(line) = input.next_command_line() {
+            match self.parse_line(&line) {
+                Ok(command) => outputs.push(self.process_command(command).message),
+                Err(error) => outputs.push(error.to_string()),
+            }
+            if self.game_over {
+                break;
+            }
+        }
+        outputs
+    }
+
+    /// Parse one command typed by the player, expanding their aliases
+    pub fn parse_line(&self, line: &str) -> Result<Command, ParseError> {
+        if let Some(command) = self.swap_answer(line) {
+            return Ok(command);
+        }
+        match expand_alias(line, &self.aliases).and_then(|line| parse_command_with(&line, &self.verbs)) {
+            // While shouting, words that only look like a misspelt verb are said aloud too
+            Err(ParseError::UnknownCommand(_)) if self.shouting => Ok(Command::Unknown(line.trim().to_lowercase())),
+            result => result,
+        }
+    }
+
+    /// Parse input that may chain several commands with ';' or "then", expanding the player's aliases
+    pub fn parse_input(&self, input: &str) -> Vec<Result<Command, ParseError>> {
+        if let Some(command) = self.swap_answer(input) {
+            return vec![Ok(command)];
+        }
+        let segments = split_commands(input);
+        if segments.is_empty() {
+            return vec![self.parse_line(input)];
+        }
+        segments.iter().map(|segment| self.parse_line(segment)).collect()
+    }
+
+    /// While the player is asked what to drop to make room, naming a carried item answers the question
+    fn swap_answer(&self, input: &str) -> Option<Command> {
+        if !matches!(self.pending_action, Some(PendingAction::Swap(_))) {
+            return None;
+        }
+        let item = canonicalize_item(input);
+        self.player.has_item(&item).then_some(Command::Drop(item))
+    }
+
+    /// Play from input typed by a player, treating the end of the input as quitting
+    pub fn play(&mut self, input: &mut dyn InputSource) -> Vec<String> {
+        let mut outputs = self.run(input);
+        if !self.game_over {
+            self.game_over = true;
+            outputs.push(self.messages.quit_confirmed.clone());
+        }
+        outputs
+    }
+
+    /// Carry out a command, returning the message to show the player
+    fn run_command(&mut self, command: Command) -> String {
+        // Whatever follows a request that needs confirming either confirms or cancels it
+        if let Some(action) = self.pending_action.take() {
+            return match (action, command) {
+                (PendingAction::Quit, Command::Quit | Command::Yes) => {
+                    self.game_over = true;
+                    self.messages.quit_confirmed.clone()
+                },
+                (PendingAction::Quit, _) => self.messages.quit_cancelled.clone(),
+                (PendingAction::Drop(item), Command::Yes) => self.drop_item(&item),
+                (PendingAction::Drop(item), _) => format!("You keep hold of the {}.", item),
+                (PendingAction::Swap(wanted), Command::Drop(item)) => self.swap_for(&canonicalize_item(&item), &wanted),
+                (PendingAction::Swap(wanted), _) => format!("You leave the {} where it is.", wanted),
+            };
+        }
+
         match command {
             Command::Go(direction) => self.handle_go(direction),
-            Command::Take(item) => self.handle_take(&item),
-            Command::Use(item) => self.handle_use(&item),
-            Command::Inventory => self.player.display_inventory(),
+            Command::Back => self.handle_back(),
+            Command::GoNamed(name) => match self.resolve_named_exit(&name) {
+                Some(direction) => self.handle_go(direction),
+                None => format!("There's no way to the {} from here.", name),
+            },
+            Command::GoTo(name) => self.handle_goto(&name),
+            Command::Take(item) => self.handle_take(&canonicalize_item(&item)),
+            Command::TakeAll => self.handle_take_all(),
+            Command::Drop(item) => self.handle_drop(&canonicalize_item(&item)),
+            Command::DropAll => self.handle_drop_all(),
+            Command::Throw(item, target) => self.handle_throw(&canonicalize_item(&item), target.as_deref()),
+            Command::Give(item, name) => self.handle_give(&canonicalize_item(&item), &name),
+            Command::Use(item) => self.handle_use(&canonicalize_item(&item)),
+            Command::UseOn(item, target) => self.handle_use_on(&canonicalize_item(&item), &target),
+            Command::Combine(a, b) => self.handle_combine(&canonicalize_item(&a), &canonicalize_item(&b)),
+            Command::Open(name) => self.handle_open(&name),
+            Command::Close(name) => self.handle_close(&name),
+            Command::Search => self.handle_search(),
+            Command::Examine => self.handle_examine(),
+            Command::Disarm(name) => self.handle_disarm(&name),
+            Command::Inventory => self.inventory_report(self.player.display_inventory()),
+            Command::SortedInventory => self.inventory_report(self.player.display_sorted_inventory()),
+            Command::Status => self.status_line(),
             Command::Look => self.look_around(),
+            Command::LookDirection(direction) => self.handle_look_direction(direction),
+            Command::Talk(name) => self.handle_talk(&name),
+            Command::Read(item) => self.handle_read(&canonicalize_item(&item)),
+            Command::Exits => {
+                let exits = self.list_exits();
+                if exits.is_empty() {
+                    "There are no exits from here.".to_string()
+                } else {
+                    format!("Exits:\n{}", exits.join("\n"))
+                }
+            },
+            Command::Map => self.render_map(),
+            Command::Retrace => self.retrace(),
+            Command::SolutionMap => self.render_solution_map(),
+            Command::Label(text) => self.handle_label(&text),
+            Command::Restart => {
+                self.reset();
+                format!("You blink, and find yourself back where it all began.\n\n{}", self.look_around())
+            },
+            Command::Undo => self.handle_undo(),
+            Command::Note(text) => {
+                self.notes.push(text);
+                "You write it in your journal.".to_string()
+            },
+            Command::Notes => self.display_notes(),
+            Command::DefineAlias(name, target) => self.define_alias(name, target),
+            Command::Aliases => self.display_aliases(),
+            Command::Hint => self.hint(),
+            Command::Scores => self.highscores_display(),
+            Command::Achievements => self.achievements_display(),
+            Command::Shout => {
+                self.shouting = !self.shouting;
+                if self.shouting {
+                    "You fill your lungs. From now on, anything the temple doesn't understand, you shout into the dark."
+                } else {
+                    "You lower your voice again."
+                }.to_string()
+            },
             Command::Help => self.display_help(),
+            Command::Intro => self.intro_text(),
             Command::Quit => {
-                self.game_over = true;
-                "Thanks for playing! Goodbye.".to_string()
+                self.pending_action = Some(PendingAction::Quit);
+                self.messages.quit_prompt.clone()
             },
-            Command::Unknown(input) => format!("I don't understand '{}'.\nType 'help' for a list of commands.", input),
+            Command::Yes => self.messages.nothing_to_confirm.clone(),
+            Command::No => self.messages.nothing_to_cancel.clone(),
+            Command::Unknown(input) if self.shouting => echo(&input),
+            Command::Unknown(input) => messages::fill(&self.messages.unknown_command, &[("input", &input)]),
         }
     }
 
     /// Handle the 'go' command
     fn handle_go(&mut self, direction: Direction) -> String {
+        // An overloaded player can't move until they lighten their load
+        if self.player.is_overloaded() {
+            return "You're overloaded and can't move. Drop something.".to_string();
+        }
+
         // Get the current room
         if let Some(current_room) = self.rooms.get(&self.player.location) {
             // Check if the direction is valid
-            if let Some(next_room_name) = current_room.exits.get(&direction) {
+            if let Some(next_room_name) = current_room.visible_exit(&direction) {
+                // Check if the way is locked and whether the player can open it
+                let lock_key = (current_room.name.clone(), direction.clone());
+                if let Some(key_item) = current_room.locks.get(&direction)
+                    && !self.unlocked.contains(&lock_key)
+                {
+                    if !self.player.has_item(key_item) {
+                        let direction = direction.to_string();
+                        return messages::fill(&self.messages.locked_exit, &[("direction", &direction), ("item", key_item)]);
+                    }
+                    self.unlocked.insert(lock_key);
+                }
+
                 // Move the player to the next room
+                self.player.previous_location = Some(self.player.location.clone());
                 self.player.location = next_room_name.clone();
+                let first_visit = self.visited.insert(next_room_name.clone());
+                self.path_history.push(next_room_name.clone());
+                if self.path_history.len() > MAX_PATH_HISTORY {
+                    self.path_history.remove(0);
+                }
+                if self.debug {
+                    self.debug_lines.push(format!("moved {} -> {} via {}", current_room.name, next_room_name, direction));
+                }
+                self.score -= MOVE_PENALTY;
+                if let Some(sink) = &self.event_sink {
+                    sink.on_enter_room(next_room_name);
+                }
+                if let Some(collapse) = self.spend_turn() {
+                    return collapse;
+                }
+
+                // Traps spring the first time a room is entered, or every time while they are armed
+                let mut trap_warning = None;
+                if let Some(room) = self.rooms.get(&self.player.location)
+                    && let Some(damage) = room.trap_damage
+                    && (first_visit || self.has_armed_trap(room))
+                {
+                    self.player.health -= damage;
+                    if !self.player.is_alive() {
+                        self.game_over = true;
+                        return "A trap springs as you step inside, and this time you are too weak to escape it. \
+                        The forgotten temple claims another explorer.\n\nGame over.".to_string();
+                    }
+                    trap_warning = Some(format!(
+                        "A trap springs as you step inside! Darts hiss from the walls and you lose {} health ({} left).",
+                        damage,
+                        self.player.health
+                    ));
+                }
 
                 // Check if this is the exit room and if the player has the required item
                 self.check_win_condition();
 
-                // Return the description of the new room
-                self.look_around()
+                // Return the description of the new room, followed by anything that happens on entering
+                // Rooms the player has been to before get a shorter description
+                let mut description = self.describe_current_room(!first_visit);
+                for message in [self.trigger_entry_event(), trap_warning].into_iter().flatten() {
+                    description.push_str(&format!("\n\n{}", message));
+                }
+                description
+            } else if let Some(message) = current_room.blocked.get(&direction) {
+                message.clone()
             } else {
-                format!("You can't go {} from here.", direction.to_string())
+                messages::fill(&self.messages.cant_go, &[("direction", direction.as_str())])
             }
         } else {
             "Error: Current room not found.".to_string()
         }
     }
 
-    /// Handle the 'take' command
-    fn handle_take(&mut self, item: &str) -> String {
-        // Get the current room
-        if let Some(current_room) = self.rooms.get_mut(&self.player.location) {
-            // Check if the item is in the room
-            if current_room.remove_item(item) {
-                // Add the item to the player's inventory
-                self.player.take_item(item);
-                format!("You take the {}.", item)
-            } else {
-                format!("There is no {} here.", item)
-            }
-        } else {
-            "Error: Current room not found.".to_string()
+    /// Let a turn pass, returning the end of the game if the temple collapses because the player
+    /// has run out of moves
+    fn spend_turn(&mut self) -> Option<String> {
+        self.turns += 1;
+        self.turns_remaining = self.turns_remaining.saturating_sub(1);
+        if self.turns_remaining > 0 {
+            return None;
         }
+
+        self.game_over = true;
+        Some("The ground heaves beneath your feet and the ceiling comes crashing down. \
+        The forgotten temple has become your tomb.\n\nGame over.".to_string())
     }
 
-    /// Handle the 'use' command
-    fn handle_use(&mut self, item: &str) -> String {
-        // Check if the player has the item
-        if self.player.has_item(item) {
-            // Get the current room
-            if let Some(current_room) = self.rooms.get(&self.player.location) {
-                // Special item interactions based on the room and item
-                match (current_room.name.as_str(), item) {
-                    ("Temple Exit", "golden idol") => {
-                        self.game_over = true;
-                        "You place the golden idol in the keyhole. With a rumble, the stone doors slowly open, \
-                        revealing the path to freedom. Sunlight streams in, blinding you momentarily. \
-                        \n\nCongratulations! You have escaped the forgotten temple!".to_string()
-                    },
-                    ("Ancient Crypt", "torch") => {
-                        "You light the torch. The crypt is now illuminated, revealing ancient inscriptions \
-                        on the walls that were previously hidden in darkness.".to_string()
-                    },
-                    ("Entrance Hall", "ancient map") => {
-                        "You examine the ancient map. It shows the layout of the temple, confirming \
-                        your suspicions about the locations of the rooms. The exit appears to be \
-                        north of the Treasure Room.".to_string()
-                    },
-                    ("Ceremonial Antechamber", "ceremonial dagger") => {
-                        "You place the ceremonial dagger on the altar. Nothing happens, but you feel \
-                        a sense of respect for the ancient rituals once performed here.".to_string()
-                    },
-                    _ => format!("You can't use the {} here.", item),
-                }
-            } else {
-                "Error: Current room not found.".to_string()
-            }
-        } else {
-            format!("You don't have a {}.", item)
+    /// Handle the 'back' command, returning to the previous room if it is still next door
+    fn handle_back(&mut self) -> String {
+        let direction = self.player.previous_location.as_ref().and_then(|previous| {
+            let room = self.rooms.get(&self.player.location)?;
+            Direction::all().into_iter().find(|direction| room.visible_exit(direction) == Some(previous))
+        });
+
+        match direction {
+            Some(direction) => self.handle_go(direction),
+            None => self.messages.cant_go_back.clone(),
         }
     }
 
-    /// Check if the player has won the game
-    fn check_win_condition(&mut self) {
-        if let Some(current_room) = self.rooms.get(&self.player.location) {
-            if current_room.is_exit {
-                if let Some(required_item) = &current_room.required_item {
-                    if self.player.has_item(required_item) {
-                        self.message = format!(
-                            "You've reached the exit with the {}! Use the item to escape.",
-                            required_item
-                        );
-                    } else {
-                        self.message = format!(
-                            "This appears to be an exit, but it's blocked. You need a {} to proceed.",
-                            required_item
-                        );
-                    }
-                }
-            }
+    /// Find the exit leading to a neighbouring room, matching any part of its name
+    pub fn resolve_named_exit(&self, name: &str) -> Option<Direction> {
+        let name = name.to_lowercase();
+        let room = self.rooms.get(&self.player.location)?;
+        Direction::all()
+            .into_iter()
+            .find(|direction| room.visible_exit(direction).is_some_and(|target| target.to_lowercase().contains(&name)))
+    }
+
+    /// Find the directions to walk to a room, going only through rooms the player has visited
+    pub fn path_to(&self, target: &str) -> Option<Vec<Direction>> {
+        let target = self.visited.iter().find(|name| name.eq_ignore_ascii_case(target))?;
+        let known: HashMap<String, Room> = self.rooms.iter()
+            .filter(|(name, _)| self.visited.contains(*name))
+            .map(|(name, room)| (name.clone(), room.clone()))
+            .collect();
+        let path = map::shortest_path(&known, &self.player.location, target)?;
+
+        path.windows(2)
+            .map(|pair| {
+                let room = &known[&pair[0]];
+                Direction::all().into_iter().find(|direction| room.exits.get(direction) == Some(&pair[1]))
+            })
+            .collect()
+    }
+
+    /// Get the shorthands the player has defined
+    pub fn aliases(&self) -> &HashMap<String, String> {
+        &self.aliases
+    }
+
+    /// Handle the 'alias' command, refusing aliases that would only ever expand into themselves
+    fn define_alias(&mut self, name: String, target: String) -> String {
+        if target.split_whitespace().next() == Some(name.as_str()) {
+            return "An alias can't stand for itself.".to_string();
         }
+        let message = format!("'{}' now means '{}'.", name, target);
+        self.aliases.insert(name, target);
+        message
     }
 
-    /// Look around the current room
-    pub fn look_around(&self) -> String {
-        // Get the current room
-        if let Some(current_room) = self.rooms.get(&self.player.location) {
-            let mut description = format!("[ {} ]\n\n{}\n", current_room.name, current_room.description);
+    /// List the player's aliases in alphabetical order
+    fn display_aliases(&self) -> String {
+        if self.aliases.is_empty() {
+            return "You haven't defined any aliases. Try 'alias gg = go north'.".to_string();
+        }
 
-            // Add exits
-            if !current_room.exits.is_empty() {
-                description.push_str("\nExits:");
-                for (direction, _) in &current_room.exits {
-                    description.push_str(&format!(" {}", direction.to_string()));
-                }
-            }
+        let mut aliases: Vec<String> = self.aliases.iter()
+            .map(|(name, target)| format!("{} = {}", name, target))
+            .collect();
+        aliases.sort();
+        format!("Aliases:\n{}", aliases.join("\n"))
+    }
 
-            // Add items
-            if !current_room.items.is_empty() {
-                description.push_str("\n\nYou see:");
-                for item in &current_room.items {
-                    description.push_str(&format!("\n- {}", item));
-                }
-            }
+    /// Check whether the item needed to escape is carried or lying somewhere the player can still reach
+    pub fn is_winnable(&self) -> bool {
+        let required: Vec<&String> = self.rooms.values()
+            .filter(|room| room.is_exit)
+            .filter_map(|room| room.required_item.as_ref())
+            .collect();
+        if required.is_empty() || required.iter().any(|item| self.player.has_item(item)) {
+            return true;
+        }
 
-            // Add any special messages
-            if !self.message.is_empty() {
-                description.push_str(&format!("\n\n{}", self.message));
+        // Walk every exit the player could pass through right now
+        let mut seen = HashSet::from([self.player.location.clone()]);
+        let mut queue = VecDeque::from([self.player.location.clone()]);
+        while let Some(name) = queue.pop_front() {
+            let Some(room) = self.rooms.get(&name) else {
+                continue;
+            };
+            let pending_spawn = room.on_enter.as_ref()
+                .filter(|_| !self.triggered.contains(&room.name))
+                .and_then(|event| event.spawn_item.as_ref());
+            let mut findable = room.items.iter()
+                .chain(&room.hidden_items)
+                .chain(room.containers.values().flat_map(|container| container.contents.iter()))
+                .chain(pending_spawn);
+            if findable.any(|item| required.iter().any(|needed| item.eq_ignore_ascii_case(needed))) {
+                return true;
             }
 
-            description
-        } else {
-            "Error: Current room not found.".to_string()
+            for (direction, next) in room.visible_exits() {
+                if let Some(key_item) = room.locks.get(direction)
+                    && !self.unlocked.contains(&(name.clone(), direction.clone()))
+                    && !self.player.has_item(key_item)
+                {
+                    continue;
+                }
+                if seen.insert(next.clone()) {
+                    queue.push_back(next.clone());
+                }
+            }
         }
+        false
     }
 
-    /// Display help text
-    fn display_help(&self) -> String {
-        "Available commands:\n\
-        - go [direction]: Move in the specified direction (north, east, south, west)\n\
-        - take [item]: Pick up an item\n\
-        - use [item]: Use an item from your inventory\n\
-        - look: Look around the current room\n\
-        - inventory: Check your inventory\n\
-        - help: Display this help text\n\
-        - quit: Exit the game".to_string()
+    /// Search breadth-first for the shortest run of commands, at most `max_depth` long, that escapes from
+    /// the current position. Only moving, taking and using items are tried.
+    pub fn solve(&self, max_depth: usize) -> Option<Vec<String>> {
+        let mut start = self.clone();
+        // Trial runs shouldn't reach the high-score table or the frontend, nor carry undo history
+        // that every position in the search would copy
+        start.highscores = None;
+        start.event_sink = None;
+        start.history.clear();
+
+        let mut seen = HashSet::from([start.solver_state()]);
+        let mut queue = VecDeque::from([(start, Vec::new())]);
+        while let Some((game, commands)) = queue.pop_front() {
+            if commands.len() >= max_depth {
+                continue;
+            }
+            for line in game.solver_moves() {
+                let Ok(command) = game.parse_line(&line) else {
+                    continue;
+                };
+                let mut next = game.clone();
+                next.process_command(command);
+                let mut path = commands.clone();
+                path.push(line);
+                if next.won {
+                    return Some(path);
+                }
+                if !next.game_over && seen.insert(next.solver_state()) {
+                    queue.push_back((next, path));
+                }
+            }
+        }
+        None
     }
 
-    /// Check if the game is over
-    pub fn is_game_over(&self) -> bool {
-        self.game_over
+    /// The commands the solver tries from the current room
+    fn solver_moves(&self) -> Vec<String> {
+        let exits = self.get_room_exits().into_iter().map(|direction| format!("go {}", direction));
+        let takes = self.get_room_items().into_iter().map(|item| format!("take {}", item));
+        let uses = self.get_sorted_inventory().into_iter().map(|item| format!("use {}", item));
+        exits.chain(takes).chain(uses).collect()
     }
 
-    /// Get the description of the current room for UI display
-    pub fn get_current_room_description(&self) -> String {
-        let current_room = self.rooms.get(&self.player.location).unwrap();
-        format!("{}\n{}", current_room.name, current_room.description)
+    /// What the solver treats as the same position: where the player is, what they carry and how the
+    /// temple has changed, but not how long it took to get there
+    fn solver_state(&self) -> String {
+        let mut flags: Vec<&String> = self.flags.iter().collect();
+        flags.sort();
+        let mut unlocked: Vec<String> = self.unlocked.iter()
+            .map(|(room, direction)| format!("{} {}", room, direction))
+            .collect();
+        unlocked.sort();
+        let mut hidden: Vec<String> = self.rooms.values()
+            .flat_map(|room| room.hidden_exits.iter().map(move |direction| format!("{} {}", room.name, direction)))
+            .collect();
+        hidden.sort();
+        format!(
+            "{}|{:?}|{:?}|{:?}|{:?}|{}",
+            self.player.location, self.get_sorted_inventory(), flags, unlocked, hidden, self.statue_disarmed
+        )
     }
 
-    /// Get a formatted display of the player's inventory
-    pub fn get_inventory_display(&self) -> String {
-        if self.player.inventory.is_empty() {
-            "Empty".to_string()
-        } else {
-            self.player.inventory.join(", ")
+    /// Whether a cave-in should happen on any of the moves made since the given turn
+    fn cave_in_due(&self, turns_before: u32) -> bool {
+        let (after, every) = (self.config.cave_in_after, self.config.cave_in_every);
+        if after == 0 || every == 0 {
+            return false;
         }
+        (turns_before + 1..=self.turns).any(|turn| turn >= after && (turn - after) % every == 0)
     }
 
-    /// Get the available exits from the current room
-    pub fn get_available_exits(&self) -> Vec<Direction> {
-        if let Some(room) = self.rooms.get(&self.player.location) {
-            room.exits.keys().cloned().collect()
+    /// Seal off a random dead end the player hasn't explored, unless doing so would leave
+    /// the temple unwinnable, returning whether anything collapsed
+    fn cave_in(&mut self) -> bool {
+        let mut candidates: Vec<String> = self.dead_ends().into_iter()
+            .filter(|name| !self.visited.contains(name))
+            .filter(|name| self.rooms.values().any(|room| room.exits.values().any(|target| target == name)))
+            .collect();
+
+        while !candidates.is_empty() {
+            let sealed = candidates.remove(self.rng.below(candidates.len()));
+            let before = self.rooms.clone();
+            for room in self.rooms.values_mut() {
+                let ways_in: Vec<Direction> = room.exits.iter()
+                    .filter(|(_, target)| **target == sealed)
+                    .map(|(direction, _)| direction.clone())
+                    .collect();
+                for direction in ways_in {
+                    room.exits.remove(&direction);
+                    room.add_blocked_exit(direction, "Fallen rubble blocks the way. That part of the temple has caved in.");
+                }
+            }
+
+            // Never cut the player off from what they need to escape
+            if self.is_winnable() {
+                return true;
+            }
+            self.rooms = before;
+        }
+        false
+    }
+
+    /// Handle the 'goto' command, walking one room at a time and stopping if the way is barred
+    fn handle_goto(&mut self, target: &str) -> String {
+        let Some(directions) = self.path_to(target) else {
+            return "You don't know a way there yet.".to_string();
+        };
+        if directions.is_empty() {
+            return format!("You're already in the {}.", self.player.location);
+        }
+
+        let mut message = String::new();
+        for direction in directions {
+            let from = self.player.location.clone();
+            message = self.handle_go(direction);
+            if self.game_over {
+                return message;
+            }
+            if self.player.location == from {
+                return format!("You set off, but are stopped in the {}: {}", from, message);
+            }
+        }
+        message
+    }
+
+    /// Handle the 'take' command
+    fn handle_take(&mut self, item: &str) -> String {
+        // Get the current room
+        if let Some(current_room) = self.rooms.get_mut(&self.player.location) {
+            // Check that the player can carry the item before picking it up
+            let in_room = current_room.has_item(item);
+            if in_room && current_room.is_fixed(item) {
+                return messages::fill(&self.messages.fixed_item, &[("item", item)]);
+            }
+            if in_room && !self.player.can_carry(item) {
+                // Offer to make room if dropping any one thing would be enough
+                let needed = self.player.current_weight() + self.player.item_weight(item) - self.player.max_weight;
+                if self.player.inventory.iter().any(|carried| self.player.item_weight(carried) >= needed) {
+                    self.pending_action = Some(PendingAction::Swap(item.to_string()));
+                    return "Your pack is full. Drop something to make room? (item name / no)".to_string();
+                }
+                return format!("The {} is too heavy to carry right now.", item);
+            }
+
+            // Check if the item is in the room
+            if let Some(taken) = current_room.remove_item(item) {
+                // Add the item to the player's inventory
+                self.player.take_item(&taken);
+                if let Some(sink) = &self.event_sink {
+                    sink.on_take_item(&taken);
+                }
+
+                self.achievements.insert(Achievement::FirstItem);
+                if taken.eq_ignore_ascii_case("golden idol") {
+                    self.achievements.insert(Achievement::FoundTheIdol);
+                }
+
+                // Items only score the first time they are picked up
+                if self.scored_items.insert(taken.to_lowercase()) {
+                    self.score += score::item_points(&taken);
+                }
+
+                // The guardian statue lashes out unless it has been jammed
+                if taken.eq_ignore_ascii_case("golden idol")
+                    && self.player.location == GUARDIAN_CHAMBER
+                    && !self.statue_disarmed
+                {
+                    if let Some(collapse) = self.spend_turn() {
+                        return collapse;
+                    }
+                    return format!(
+                        "{} The statue's arms sweep down as you lift it, \
+                        and you lose precious time scrambling clear.",
+                        messages::fill(&self.messages.take_item, &[("item", &taken)])
+                    );
+                }
+                messages::fill(&self.messages.take_item, &[("item", &taken)])
+            } else {
+                messages::fill(&self.messages.no_item_here, &[("item", item)])
+            }
         } else {
-            Vec::new()
+            "Error: Current room not found.".to_string()
+        }
+    }
+
+    /// Handle the 'take all' command, picking up whatever the player can carry
+    fn handle_take_all(&mut self) -> String {
+        // Collect the names first, since taking changes the room's items
+        let items = match self.rooms.get(&self.player.location) {
+            Some(room) => room.items.iter().filter(|item| !room.is_fixed(item)).cloned().collect::<Vec<_>>(),
+            None => return "Error: Current room not found.".to_string(),
+        };
+        if items.is_empty() {
+            return self.messages.nothing_to_take.clone();
+        }
+
+        let mut taken = Vec::new();
+        let mut too_heavy = Vec::new();
+        let mut notes = Vec::new();
+        for item in items {
+            if !self.player.can_carry(&item) {
+                too_heavy.push(item);
+                continue;
+            }
+            // Keep anything said beyond the plain confirmation, such as the statue lashing out
+            let message = self.handle_take(&item);
+            if let Some(extra) = message.strip_prefix(&messages::fill(&self.messages.take_item, &[("item", &item)]))
+                && !extra.trim().is_empty()
+            {
+                notes.push(extra.trim().to_string());
+            }
+            taken.push(item);
+        }
+
+        let mut message = if taken.is_empty() {
+            "You can't carry any of it.".to_string()
+        } else {
+            format!("You take: {}.", taken.join(", "))
+        };
+        if !too_heavy.is_empty() {
+            let left: Vec<String> = too_heavy.iter().map(|item| format!("{} (too heavy)", item)).collect();
+            message.push_str(&format!(" You leave behind: {}.", left.join(", ")));
+        }
+        for note in notes {
+            message.push_str(&format!(" {}", note));
+        }
+        message
+    }
+
+    /// Handle the 'drop' command, asking first if the item is needed to escape
+    fn handle_drop(&mut self, item: &str) -> String {
+        if self.is_required_item(item) && self.player.has_item(item) {
+            return self.confirm_drop(item);
+        }
+
+        self.drop_item(item)
+    }
+
+    /// Ask the player to confirm dropping an item they need to escape
+    fn confirm_drop(&mut self, item: &str) -> String {
+        self.pending_action = Some(PendingAction::Drop(item.to_string()));
+        format!("The {} seems important. Drop it anyway? (y/n)", item)
+    }
+
+    /// Drop a carried item to make room for another lying in the room, then take that one
+    fn swap_for(&mut self, item: &str, wanted: &str) -> String {
+        if !self.player.has_item(item) {
+            return format!("You don't have a {}, so you leave the {} where it is.", item, wanted);
+        }
+
+        let dropped = self.drop_item(item);
+        if !self.player.can_carry(wanted) {
+            return format!("{} Even so, the {} is still too heavy to carry.", dropped, wanted);
+        }
+        format!("{} {}", dropped, self.handle_take(wanted))
+    }
+
+    /// Move an item from the inventory to the current room
+    fn drop_item(&mut self, item: &str) -> String {
+        // Get the current room
+        if let Some(current_room) = self.rooms.get_mut(&self.player.location) {
+            // Check if the player is carrying the item
+            if let Some(dropped) = self.player.drop_item(item) {
+                current_room.add_item(&dropped);
+                messages::fill(&self.messages.drop_item, &[("item", &dropped)])
+            } else {
+                messages::fill(&self.messages.not_carrying, &[("item", item)])
+            }
+        } else {
+            "Error: Current room not found.".to_string()
+        }
+    }
+
+    /// Fire the current room's entry event if it hasn't fired yet, returning its message
+    fn trigger_entry_event(&mut self) -> Option<String> {
+        let room = self.rooms.get_mut(&self.player.location)?;
+        let event = room.on_enter.clone()?;
+        if !self.triggered.insert(room.name.clone()) {
+            return None;
+        }
+
+        if let Some(item) = &event.spawn_item {
+            room.add_item(item);
+        }
+        if self.debug {
+            self.debug_lines.push(format!("entry event fired in {}", self.player.location));
+        }
+        Some(event.message)
+    }
+
+    /// Handle the 'throw' command, leaving the thrown item in the room
+    fn handle_throw(&mut self, item: &str, target: Option<&str>) -> String {
+        if self.is_required_item(item) && self.player.has_item(item) {
+            return format!("The {} seems far too important to throw around.", item);
+        }
+        let Some(thrown) = self.player.drop_item(item) else {
+            return messages::fill(&self.messages.not_carrying, &[("item", item)]);
+        };
+        if let Some(current_room) = self.rooms.get_mut(&self.player.location) {
+            current_room.add_item(&thrown);
+        }
+
+        if thrown == "ceremonial dagger"
+            && matches!(target, Some("statue" | "guardian statue"))
+            && self.player.location == GUARDIAN_CHAMBER
+            && !self.statue_disarmed
+        {
+            self.statue_disarmed = true;
+            return "You hurl the ceremonial dagger at the statue. It lodges in a joint of the many arms \
+                with a grinding crunch, and the arms shudder to a halt. The idol looks safe to take now.".to_string();
+        }
+
+        format!("You throw the {}. It clatters to the floor.", thrown)
+    }
+
+    /// Handle the 'drop all' command, leaving everything carried in the room except what is
+    /// needed to escape, which the player is asked about first
+    fn handle_drop_all(&mut self) -> String {
+        if self.player.inventory.is_empty() {
+            return "You aren't carrying anything.".to_string();
+        }
+
+        let (kept, dropped): (Vec<String>, Vec<String>) = std::mem::take(&mut self.player.inventory)
+            .into_iter()
+            .partition(|item| self.is_required_item(item));
+        self.player.inventory = kept;
+        let Some(current_room) = self.rooms.get_mut(&self.player.location) else {
+            return "Error: Current room not found.".to_string();
+        };
+        for item in &dropped {
+            current_room.add_item(item);
+        }
+
+        let mut parts = Vec::new();
+        if !dropped.is_empty() {
+            parts.push(format!("You drop: {}.", dropped.join(", ")));
+        }
+        if let Some(item) = self.player.inventory.first().cloned() {
+            parts.push(self.confirm_drop(&item));
+        }
+        parts.join(" ")
+    }
+
+    /// Handle the 'search' command
+    fn handle_search(&mut self) -> String {
+        // Searching takes time whether or not anything is found
+        if let Some(collapse) = self.spend_turn() {
+            return collapse;
+        }
+        let hard_mode = self.config.difficulty == Difficulty::Hard;
+
+        if let Some(current_room) = self.rooms.get_mut(&self.player.location) {
+            // In hard mode, a trapped room may spring its trap instead
+            if hard_mode && current_room.trapped && self.rng.chance(TRAP_CHANCE) {
+                current_room.trapped = false;
+                return "As you feel along the walls, a stone plate sinks beneath your hand. \
+                    Darts hiss from hidden slots and you throw yourself to the floor just in time. \
+                    The trap is spent, but you find nothing.".to_string();
+            }
+
+            let found = current_room.reveal_hidden_items();
+            if found.is_empty() {
+                "You find nothing new.".to_string()
+            } else {
+                format!("You search carefully and find: {}", found.join(", "))
+            }
+        } else {
+            "Error: Current room not found.".to_string()
+        }
+    }
+
+    /// Handle the 'examine room' command, looking closely for details a glance would miss
+    fn handle_examine(&mut self) -> String {
+        // Studying the room takes time
+        if let Some(collapse) = self.spend_turn() {
+            return collapse;
+        }
+        match self.rooms.get(&self.player.location).and_then(|room| room.detail_text.clone()) {
+            Some(detail) => detail,
+            None => "You look closely, but notice nothing out of the ordinary.".to_string(),
+        }
+    }
+
+    /// Handle the 'combine [item] with [item]' command
+    fn handle_combine(&mut self, a: &str, b: &str) -> String {
+        for item in [a, b] {
+            if !self.player.has_item(item) {
+                return messages::fill(&self.messages.not_carrying, &[("item", item)]);
+            }
+        }
+
+        let recipes = crafting::default_recipes();
+        let Some(result) = crafting::find_recipe(&recipes, a, b) else {
+            return "Those items can't be combined.".to_string();
+        };
+
+        self.player.drop_item(a);
+        self.player.drop_item(b);
+        self.player.take_item(result);
+        format!("You combine the {} and the {} into a {}.", a, b, result)
+    }
+
+    /// Handle the 'open' command
+    fn handle_open(&mut self, name: &str) -> String {
+        let Some(container) = self.rooms.get_mut(&self.player.location)
+            .and_then(|room| room.containers.get_mut(name))
+        else {
+            return format!("There's no {} here to open.", name);
+        };
+        if container.open {
+            return format!("The {} is already open.", name);
+        }
+
+        container.open = true;
+        if container.contents.is_empty() {
+            format!("You open the {}. It's empty.", name)
+        } else {
+            format!("You open the {}. Inside you find: {}.", name, container.contents.join(", "))
+        }
+    }
+
+    /// Handle the 'close' command
+    fn handle_close(&mut self, name: &str) -> String {
+        let Some(container) = self.rooms.get_mut(&self.player.location)
+            .and_then(|room| room.containers.get_mut(name))
+        else {
+            return format!("There's no {} here to close.", name);
+        };
+        if !container.open {
+            return format!("The {} is already closed.", name);
+        }
+
+        container.open = false;
+        format!("You close the {}.", name)
+    }
+
+    /// Handle the 'undo' command
+    fn handle_undo(&mut self) -> String {
+        match self.history.pop() {
+            Some(snapshot) => {
+                self.restore(snapshot);
+                "Undone.".to_string()
+            }
+            None => "Nothing to undo.".to_string(),
+        }
+    }
+
+    /// Capture the parts of the game an action can change
+    fn snapshot(&self) -> GameSnapshot {
+        GameSnapshot {
+            rooms: self.rooms.clone(),
+            player: self.player.clone(),
+            visited: self.visited.clone(),
+            path_history: self.path_history.clone(),
+            game_over: self.game_over,
+            won: self.won,
+            ending: self.ending,
+            achievements: self.achievements.clone(),
+            unlocked: self.unlocked.clone(),
+            disarmed: self.disarmed.clone(),
+            flags: self.flags.clone(),
+            message: self.message.clone(),
+            turns: self.turns,
+            turns_remaining: self.turns_remaining,
+            score: self.score,
+            scored_items: self.scored_items.clone(),
+            statue_disarmed: self.statue_disarmed,
+            triggered: self.triggered.clone(),
+            notes: self.notes.clone(),
+            player_labels: self.player_labels.clone(),
+            rng: self.rng.clone(),
+        }
+    }
+
+    /// Put the game back into a previously captured state
+    fn restore(&mut self, snapshot: GameSnapshot) {
+        self.rooms = snapshot.rooms;
+        self.player = snapshot.player;
+        self.visited = snapshot.visited;
+        self.path_history = snapshot.path_history;
+        self.game_over = snapshot.game_over;
+        self.won = snapshot.won;
+        self.ending = snapshot.ending;
+        self.achievements = snapshot.achievements;
+        self.unlocked = snapshot.unlocked;
+        self.disarmed = snapshot.disarmed;
+        self.flags = snapshot.flags;
+        self.message = snapshot.message;
+        self.turns = snapshot.turns;
+        self.turns_remaining = snapshot.turns_remaining;
+        self.score = snapshot.score;
+        self.scored_items = snapshot.scored_items;
+        self.statue_disarmed = snapshot.statue_disarmed;
+        self.triggered = snapshot.triggered;
+        self.notes = snapshot.notes;
+        self.player_labels = snapshot.player_labels;
+        self.rng = snapshot.rng;
+    }
+
+    /// Handle the 'talk' command, saying each line in turn and then repeating the last one
+    fn handle_talk(&mut self, name: &str) -> String {
+        let npc = self.rooms.get(&self.player.location)
+            .and_then(|room| room.npcs.iter().find(|npc| npc.name.eq_ignore_ascii_case(name)));
+        let Some(npc) = npc else {
+            return format!("There's no one here called {}.", name);
+        };
+        let Some(last) = npc.dialogue.len().checked_sub(1) else {
+            return format!("The {} says nothing.", npc.name);
+        };
+
+        let progress = self.dialogue_progress.entry(npc.name.clone()).or_insert(0);
+        let line = &npc.dialogue[(*progress).min(last)];
+        *progress += 1;
+        format!("The {} says: \"{}\"", npc.name, line)
+    }
+
+    /// Handle the 'give' command, handing an item to a character who has a use for it
+    fn handle_give(&mut self, item: &str, name: &str) -> String {
+        if !self.player.has_item(item) {
+            return messages::fill(&self.messages.not_carrying, &[("item", item)]);
+        }
+        let npc = self.rooms.get(&self.player.location)
+            .and_then(|room| room.npcs.iter().find(|npc| npc.name.eq_ignore_ascii_case(name)));
+        let Some(npc) = npc else {
+            return format!("There's no one here called {}.", name);
+        };
+
+        match (npc.name.as_str(), item) {
+            ("guardian", "gold coin") => {
+                self.player.drop_item(item);
+                "The guardian's ghostly hand closes around the coin, and for a moment the chamber grows warm. \
+                \"An honest offering. Hear me, then: the pedestal among the treasures waits for the idol. \
+                Set it there, and the wall will show you the way north.\"".to_string()
+            }
+            _ => format!("The {} has no use for the {}.", npc.name, item),
+        }
+    }
+
+    /// Whether the room has a trap the player hasn't disarmed yet
+    fn has_armed_trap(&self, room: &Room) -> bool {
+        room.traps.keys().any(|trap| !self.disarmed.contains(&(room.name.clone(), trap.clone())))
+    }
+
+    /// Handle the 'disarm' command, making a trap safe if the player holds the right tool
+    fn handle_disarm(&mut self, name: &str) -> String {
+        let trap = self.rooms.get(&self.player.location)
+            .and_then(|room| room.traps.iter().find(|(trap, _)| trap.eq_ignore_ascii_case(name)));
+        let Some((trap, tool)) = trap else {
+            return format!("There's no {} here to disarm.", name);
+        };
+
+        let key = (self.player.location.clone(), trap.clone());
+        if self.disarmed.contains(&key) {
+            return format!("The {} is already disarmed.", trap);
+        }
+        if !self.player.has_item(tool) {
+            return format!("You need a {} to disarm the {}.", tool, trap);
+        }
+
+        let message = format!("You work the {} into the {}'s mechanism until it clicks and falls still. \
+            The {} is disarmed.", tool, trap, trap);
+        self.disarmed.insert(key);
+        message
+    }
+
+    /// Handle the 'label' command
+    fn handle_label(&mut self, text: &str) -> String {
+        self.player_labels.insert(self.player.location.clone(), text.to_string());
+        format!("You mark the {} as \"{}\".", self.player.location, text)
+    }
+
+    /// Handle the 'use' command
+    fn handle_use(&mut self, item: &str) -> String {
+        // Check if the player has the item
+        if !self.player.has_item(item) {
+            return messages::fill(&self.messages.not_carrying, &[("item", item)]);
+        }
+        // Get the current room
+        let Some(current_room) = self.rooms.get(&self.player.location) else {
+            return "Error: Current room not found.".to_string();
+        };
+
+        // Special item interactions based on the room and item
+        let mut message = match (current_room.name.as_str(), item) {
+            ("Temple Exit", "golden idol") => {
+                let summary = self.finish(Ending::TrueEscape);
+                format!(
+                    "You place the golden idol in the keyhole. With a rumble, the stone doors slowly open, \
+                    revealing the path to freedom. Sunlight streams in, blinding you momentarily. \
+                    \n\nCongratulations! You have escaped the forgotten temple!{}",
+                    summary
+                )
+            },
+            ("Treasure Room", "golden idol") => self.place_idol(),
+            ("Ancient Crypt", "torch") => {
+                self.flags.insert(FLAG_TORCH_LIT.to_string());
+                self.achievements.insert(Achievement::LitTheCrypt);
+                "You light the torch. The crypt is now illuminated, revealing ancient inscriptions \
+                on the walls that were previously hidden in darkness.".to_string()
+            },
+            ("Entrance Hall", "ancient map") => {
+                "You examine the ancient map. It shows the layout of the temple, confirming \
+                your suspicions about the locations of the rooms. The exit appears to be \
+                north of the Treasure Room.".to_string()
+            },
+            ("Ceremonial Antechamber", "ceremonial dagger") => {
+                "You place the ceremonial dagger on the altar. Nothing happens, but you feel \
+                a sense of respect for the ancient rituals once performed here.".to_string()
+            },
+            _ => messages::fill(&self.messages.cant_use_here, &[("item", item)]),
+        };
+
+        // Consumables wear out with use, though never the item needed to escape
+        if !self.is_required_item(item) && self.player.use_up(item) {
+            message.push_str(&format!("\n\nYour {} sputters and dies.", item));
+        }
+        message
+    }
+
+    /// Handle the 'read' command, for writing in the room or on a carried item
+    fn handle_read(&mut self, target: &str) -> String {
+        let target = match target {
+            "tapestry" | "star tapestry" => "star map",
+            target => target,
+        };
+
+        let readable = self.rooms.get(&self.player.location)
+            .and_then(|room| room.readables.get(target))
+            .cloned();
+        if let Some(text) = readable {
+            let in_crypt = self.player.location == "Ancient Crypt";
+            return match target {
+                "star map" if in_crypt => self.read_star_map(&text),
+                _ if in_crypt && !self.flags.contains(FLAG_TORCH_LIT) => {
+                    "It's too dark in here to read anything.".to_string()
+                },
+                _ => {
+                    self.record_discovery(format!("{} ({}): {}", target, self.player.location, text));
+                    text
+                },
+            };
+        }
+
+        if self.player.has_item(target) {
+            return match target {
+                "ancient map" => ANCIENT_MAP_DIAGRAM.to_string(),
+                _ => format!("There's nothing written on the {}.", target),
+            };
+        }
+
+        "There's nothing like that to read here.".to_string()
+    }
+
+    /// Add a discovery to the journal, unless it's already written there
+    fn record_discovery(&mut self, entry: String) {
+        if !self.notes.contains(&entry) {
+            self.notes.push(entry);
+        }
+    }
+
+    /// List the journal entries, numbered
+    fn display_notes(&self) -> String {
+        if self.notes.is_empty() {
+            return "Your journal is empty. Use 'note [text]' to write in it.".to_string();
+        }
+
+        let mut journal = "Your journal:".to_string();
+        for (number, note) in self.notes.iter().enumerate() {
+            journal.push_str(&format!("\n{}. {}", number + 1, note));
+        }
+        journal
+    }
+
+    /// Read the crypt's star map by torchlight, revealing the secret passage it hides
+    fn read_star_map(&mut self, text: &str) -> String {
+        if !self.flags.contains(FLAG_STAR_CLUE) {
+            return "Only ashes remain where the tapestry once hung.".to_string();
+        }
+        if !self.flags.contains(FLAG_TORCH_LIT) {
+            return "It's too dark in here to make out the stars.".to_string();
+        }
+        if self.flags.contains(FLAG_PASSAGE_FOUND) {
+            return "The stars still point to the loose stone in the east wall.".to_string();
+        }
+
+        self.flags.insert(FLAG_PASSAGE_FOUND.to_string());
+        let passage = Room::new(
+            SECRET_PASSAGE,
+            "A cramped tunnel, barely wide enough to crawl through, winds away from the crypt. \
+            A faint breeze carries the smell of fresh air.",
+            true,
+            None,
+        );
+        self.rooms.insert(passage.name.clone(), passage);
+        link_rooms(&mut self.rooms, "Ancient Crypt", Direction::East, SECRET_PASSAGE);
+        self.record_discovery("The star map points to a hidden passage in the crypt's east wall.".to_string());
+
+        format!(
+            "{} By torchlight they form a pattern: every constellation points to a single stone in the east wall. \
+            You press it, and the stone grinds aside to reveal a narrow passage.",
+            text
+        )
+    }
+
+    /// End the game with the given ending, returning the summary to show after its text
+    fn finish(&mut self, ending: Ending) -> String {
+        self.game_over = true;
+        self.won = true;
+        self.ending = Some(ending);
+        self.achievements.insert(Achievement::Escaped);
+        self.score += ending.bonus();
+        format!("\n\nEnding: {}\n\nFinal score: {}", ending.ranking(), self.score)
+    }
+
+    /// List the achievements, naming only the ones the player has unlocked
+    fn achievements_display(&self) -> String {
+        let lines: Vec<String> = Achievement::all().iter()
+            .map(|achievement| {
+                if self.achievements.contains(achievement) {
+                    format!("- {}: {}", achievement.title(), achievement.description())
+                } else {
+                    "- ???".to_string()
+                }
+            })
+            .collect();
+        format!("Achievements ({}/{}):\n{}", self.achievements.len(), Achievement::all().len(), lines.join("\n"))
+    }
+
+    /// Handle the 'use [item] on [target]' command
+    fn handle_use_on(&mut self, item: &str, target: &str) -> String {
+        // Check if the player has the item
+        if !self.player.has_item(item) {
+            return messages::fill(&self.messages.not_carrying, &[("item", item)]);
+        }
+
+        match (self.player.location.as_str(), item, target) {
+            ("Ancient Crypt", "torch", "tapestry" | "star tapestry") => self.burn_tapestry(),
+            (GUARDIAN_CHAMBER, "ceremonial dagger", "statue" | "guardian statue") => self.wedge_statue(),
+            ("Treasure Room", "golden idol", "pedestal") => self.place_idol(),
+            _ => format!("You can't use the {} on the {}.", item, target),
+        }
+    }
+
+    /// Set the idol on the Treasure Room pedestal, revealing the way north to the exit
+    fn place_idol(&mut self) -> String {
+        let Some(room) = self.rooms.get_mut("Treasure Room") else {
+            return "Error: Current room not found.".to_string();
+        };
+        if !room.reveal_exit(&Direction::North) {
+            return "The passage north already stands open.".to_string();
+        }
+
+        "You set the golden idol on the pedestal. It sinks a finger's width into the stone, and with a deep \
+        rumble a section of the northern wall slides aside, revealing a narrow passage. The pedestal rises \
+        again and you lift the idol back off it. You have a feeling you'll need it.".to_string()
+    }
+
+    /// Jam the guardian statue's arms with the dagger, keeping hold of the blade
+    fn wedge_statue(&mut self) -> String {
+        if self.statue_disarmed {
+            return "The statue's arms are already jammed fast.".to_string();
+        }
+
+        self.statue_disarmed = true;
+        "You work the ceremonial dagger into a joint of the statue's many arms and lean on it. \
+        Something deep inside gives with a crack, and the arms sag, lifeless. You pull the blade free. \
+        The idol looks safe to take now.".to_string()
+    }
+
+    /// Burn the crypt's star tapestry, losing its clue but uncovering the alcove behind it
+    fn burn_tapestry(&mut self) -> String {
+        if self.flags.contains(FLAG_TAPESTRY_BURNED) {
+            return "Only ashes remain where the tapestry once hung.".to_string();
+        }
+        if !self.flags.contains(FLAG_TORCH_LIT) {
+            return "The torch isn't lit.".to_string();
+        }
+
+        self.flags.remove(FLAG_STAR_CLUE);
+        self.flags.insert(FLAG_TAPESTRY_BURNED.to_string());
+
+        if let Some(crypt) = self.rooms.get_mut("Ancient Crypt") {
+            crypt.description = "The air is stale in this dark crypt. Stone sarcophagi line the walls, \
+                their carved lids depicting the deceased in repose. \
+                Scorched stone marks where a tapestry once hung, framing a small alcove in the far wall.".to_string();
+            crypt.add_item("jade scarab");
+        }
+
+        "You hold the torch to the tapestry. The ancient fabric catches at once, and the map of the stars \
+        curls into ash before you can study it. As the smoke clears, a small alcove is revealed behind it, \
+        holding a jade scarab.".to_string()
+    }
+
+    /// Check if the player has won the game
+    fn check_win_condition(&mut self) {
+        // Exits that need nothing lead straight out, by the narrow way
+        if let Some(current_room) = self.rooms.get(&self.player.location)
+            && current_room.is_exit
+            && current_room.required_item.is_none()
+        {
+            let summary = self.finish(Ending::NarrowEscape);
+            self.message = format!(
+                "You squeeze through the last of the tunnel and tumble out onto a hillside, bruised and filthy \
+                but alive. Behind you, the temple's secrets stay buried.\n\nYou made a narrow escape!{}",
+                summary
+            );
+            return;
+        }
+
+        if let Some(current_room) = self.rooms.get(&self.player.location)
+            && current_room.is_exit
+            && let Some(required_item) = &current_room.required_item
+        {
+            if self.player.has_item(required_item) {
+                self.message = format!(
+                    "You've reached the exit with the {}! Use the item to escape.",
+                    required_item
+                );
+            } else if self.is_winnable() {
+                self.message = format!(
+                    "This appears to be an exit, but it's blocked. You need a {} to proceed.",
+                    required_item
+                );
+            } else {
+                self.message = format!(
+                    "This appears to be an exit, but it's blocked. You need a {}, and there's no longer \
+                    any way to get one. Type 'undo' to take back your last action, or 'restart' to begin again.",
+                    required_item
+                );
+            }
+        }
+    }
+
+    /// Whether an exit needs the item to let the player out
+    fn is_required_item(&self, item: &str) -> bool {
+        self.rooms.values()
+            .filter_map(|room| room.required_item.as_ref())
+            .any(|required| required.eq_ignore_ascii_case(item))
+    }
+
+    /// Look around the current room
+    pub fn look_around(&self) -> String {
+        self.describe_current_room(false)
+    }
+
+    /// Whether the player is standing in the dark, unable to make out the room
+    fn is_dark(&self) -> bool {
+        self.player.location == "Ancient Crypt"
+            && !self.flags.contains(FLAG_TORCH_LIT)
+            && !self.flags.contains(FLAG_TAPESTRY_BURNED)
+    }
+
+    /// Describe the current room, leaving out its prose if `brief`, followed by the
+    /// player's note, any collapse warning and special messages
+    fn describe_current_room(&self, brief: bool) -> String {
+        // Get the current room
+        if let Some(current_room) = self.rooms.get(&self.player.location) {
+            let mut description = current_room.describe(DescribeOptions { brief, dark: self.is_dark() });
+
+            // Add the player's own note
+            if let Some(label) = self.player_labels.get(&current_room.name) {
+                description.push_str(&format!("\n\nYour note: \"{}\"", label));
+            }
+
+            // Warn the player when the temple is close to collapsing
+            if self.turns_remaining < COLLAPSE_WARNING_TURNS {
+                description.push_str(&format!(
+                    "\n\nThe ground trembles — {} moves before collapse!",
+                    self.turns_remaining
+                ));
+            }
+
+            // Add any special messages
+            if !self.message.is_empty() {
+                description.push_str(&format!("\n\n{}", self.message));
+            }
+
+            description
+        } else {
+            "Error: Current room not found.".to_string()
         }
     }
 
-    /// Get items in the current room
-    pub fn get_room_items(&self) -> Vec<String> {
-        if let Some(room) = self.rooms.get(&self.player.location) {
-            room.items.clone()
-        } else {
-            Vec::new()
-        }
+    /// Glance through an exit at the room beyond, without moving
+    fn handle_look_direction(&self, direction: Direction) -> String {
+        let next_room = self.rooms.get(&self.player.location)
+            .and_then(|room| room.visible_exit(&direction))
+            .and_then(|name| self.rooms.get(name));
+        let Some(next_room) = next_room else {
+            return "There's nothing that way.".to_string();
+        };
+
+        let first_sentence = match next_room.description.find(". ") {
+            Some(end) => &next_room.description[..=end],
+            None => next_room.description.as_str(),
+        };
+        format!("To the {} lies the {}. {}", direction, next_room.name, first_sentence)
+    }
+
+    /// List where each exit from the current room leads, hiding rooms the player hasn't visited
+    pub fn list_exits(&self) -> Vec<String> {
+        let Some(current_room) = self.rooms.get(&self.player.location) else {
+            return Vec::new();
+        };
+
+        Direction::all()
+            .iter()
+            .filter_map(|direction| {
+                current_room.visible_exit(direction).map(|destination| {
+                    let name = if self.visited.contains(destination) { destination.as_str() } else { "???" };
+                    format!("{} -> {}", direction, name)
+                })
+            })
+            .collect()
+    }
+
+    /// Draw a map of the rooms the player has visited
+    pub fn render_map(&self) -> String {
+        let layout = map::compute_layout(&self.rooms, &self.start);
+        let dead_ends = self.dead_ends();
+        map::render(&self.rooms, &layout, &self.footsteps(), &self.player_labels, &[], &dead_ends, &self.player.location)
+    }
+
+    /// The rooms the player has walked through, in order
+    pub fn path_history(&self) -> &[String] {
+        &self.path_history
+    }
+
+    /// List the visited rooms in the order the player first entered them
+    pub fn footsteps(&self) -> Vec<String> {
+        // Rooms that have dropped off the trail were visited before anything on it
+        let mut footsteps: Vec<String> = self.visited.iter()
+            .filter(|name| !self.path_history.contains(name))
+            .cloned()
+            .collect();
+        footsteps.sort();
+        for name in &self.path_history {
+            if !footsteps.contains(name) {
+                footsteps.push(name.clone());
+            }
+        }
+        footsteps
+    }
+
+    /// Handle the 'retrace' command, listing the directions taken from room to room
+    fn retrace(&self) -> String {
+        let directions: Vec<String> = self.path_history.windows(2)
+            .filter_map(|step| {
+                let room = self.rooms.get(&step[0])?;
+                room.exits.iter()
+                    .find(|(_, target)| **target == step[1])
+                    .map(|(direction, _)| direction.to_string())
+            })
+            .collect();
+        if directions.is_empty() {
+            return "You haven't gone anywhere yet.".to_string();
+        }
+        format!("Your footsteps from the {}: {}", self.path_history[0], directions.join(", "))
+    }
+
+    /// List the rooms with only one way in or out, apart from the starting room and the exit
+    pub fn dead_ends(&self) -> Vec<String> {
+        let mut dead_ends: Vec<String> = self.rooms.values()
+            .filter(|room| room.exit_count() == 1 && room.name != self.start && !room.is_exit)
+            .map(|room| room.name.clone())
+            .collect();
+        dead_ends.sort();
+        dead_ends
+    }
+
+    /// Draw the map with the fastest route from the start to the exit, once the game is won
+    pub fn render_solution_map(&self) -> String {
+        if !self.won {
+            return "No peeking! The solution is only revealed once you have escaped.".to_string();
+        }
+
+        let route = self.rooms.values()
+            .filter(|room| room.is_exit)
+            .filter_map(|room| map::shortest_path(&self.rooms, &self.start, &room.name))
+            .min_by_key(|path| path.len())
+            .unwrap_or_default();
+
+        let mut shown = self.footsteps();
+        shown.extend(route.iter().filter(|name| !self.visited.contains(*name)).cloned());
+
+        let layout = map::compute_layout(&self.rooms, &self.start);
+        let mut map = map::render(&self.rooms, &layout, &shown, &self.player_labels, &route, &[], &self.player.location);
+        map.push_str(&format!("\n\nFastest route: {}", route.join(" -> ")));
+        map
+    }
+
+    /// Suggest the next useful action based on what the player carries and where they are
+    pub fn hint(&self) -> String {
+        if !self.config.hints_enabled {
+            return format!("You are on your own here. Hints are not available on {} difficulty.", self.config.difficulty.name());
+        }
+
+        let location = &self.player.location;
+
+        // Aim for the closest exit the player can reach
+        let Some((exit, exit_path)) = self.rooms.values()
+            .filter(|room| room.is_exit)
+            .filter_map(|room| map::shortest_path(&self.rooms, location, &room.name).map(|path| (room, path)))
+            .min_by_key(|(_, path)| path.len())
+        else {
+            return "You sense no way out from here. Try exploring elsewhere.".to_string();
+        };
+
+        // Fetch the item the exit needs first
+        if let Some(required_item) = &exit.required_item
+            && !self.player.has_item(required_item)
+        {
+            let item_path = self.rooms.values()
+                .filter(|room| room.items.iter().any(|i| i.eq_ignore_ascii_case(required_item)))
+                .filter_map(|room| map::shortest_path(&self.rooms, location, &room.name))
+                .min_by_key(|path| path.len());
+
+            return match item_path {
+                Some(path) if path.len() == 1 => format!("The {} is right here. Try taking it.", required_item),
+                Some(path) => format!(
+                    "You'll need the {} to escape. Try heading {} towards the {}.",
+                    required_item,
+                    self.next_step(&path),
+                    path[path.len() - 1]
+                ),
+                None => format!("You'll need the {} to escape. Perhaps it is hidden somewhere.", required_item),
+            };
+        }
+
+        // The way on may still need revealing
+        if exit_path.len() > 1
+            && self.rooms.get(location).is_some_and(|room| room.visible_exits().all(|(_, target)| *target != exit_path[1]))
+        {
+            return "The way onwards is hidden here. Read what is written in this room for a clue.".to_string();
+        }
+
+        if exit_path.len() > 1 {
+            return format!(
+                "You have what you need. Head {} towards the {}.",
+                self.next_step(&exit_path),
+                exit.name
+            );
+        }
+
+        match &exit.required_item {
+            Some(required_item) => format!("This is the way out. Try using the {}.", required_item),
+            None => "This is the way out.".to_string(),
+        }
+    }
+
+    /// Get the name of the direction leading to the second room of a path
+    fn next_step(&self, path: &[String]) -> &str {
+        self.rooms.get(&path[0])
+            .and_then(|room| room.exits.iter().find(|(_, target)| **target == path[1]))
+            .map(|(direction, _)| direction.as_str())
+            .unwrap_or("onwards")
+    }
+
+    /// Display help text
+    fn display_help(&self) -> String {
+        self.messages.help.clone()
+    }
+
+    /// Get the number of turns that have passed
+    pub fn turns(&self) -> u32 {
+        self.turns
+    }
+
+    /// Get the number of moves left before the temple collapses
+    pub fn turns_remaining(&self) -> u32 {
+        self.turns_remaining
+    }
+
+    /// Follow an inventory listing with the player's health
+    fn inventory_report(&self, inventory: String) -> String {
+        format!("{}\nHealth: {}", inventory.trim_end(), self.player.health)
+    }
+
+    /// Summarize the player's location, load, moves, health and remaining turns on one line
+    pub fn status_line(&self) -> String {
+        format!(
+            "Location: {} | Items: {} ({}/{} weight) | Moves: {} | Health: {} | Turns left: {}",
+            self.player.location,
+            self.player.inventory.len(),
+            self.player.current_weight(),
+            self.player.max_weight,
+            self.turns,
+            self.player.health,
+            self.turns_remaining
+        )
+    }
+
+    /// Get the player's current score
+    pub fn score(&self) -> i32 {
+        self.score
+    }
+
+    /// Describe how the current score was earned
+    pub fn score_breakdown(&self) -> String {
+        let treasure: i32 = self.scored_items.iter().map(|item| score::item_points(item)).sum();
+        let escape = self.ending.map_or(0, |ending| ending.bonus());
+        // Everything else was lost to moving around
+        let movement = self.score - treasure - escape;
+
+        format!(
+            "Treasure: {}\nMovement: {}\nEscape bonus: {}\nTotal: {}",
+            treasure, movement, escape, self.score
+        )
+    }
+
+    /// Get how the player escaped, if they have
+    pub fn ending(&self) -> Option<Ending> {
+        self.ending
+    }
+
+    /// Check if the game is over
+    pub fn is_game_over(&self) -> bool {
+        self.game_over
+    }
+
+    /// Get the room the player is in
+    pub fn get_current_room(&self) -> Option<&Room> {
+        self.rooms.get(&self.player.location)
+    }
+
+    /// Get the description of the current room for UI display
+    pub fn get_current_room_description(&self) -> String {
+        match self.get_current_room() {
+            Some(current_room) => format!("{}\n{}", current_room.name, current_room.description),
+            None => format!("You are lost somewhere unknown ({}).", self.player.location),
+        }
+    }
+
+    /// Get a formatted display of the player's inventory
+    pub fn get_inventory_display(&self) -> String {
+        if self.player.inventory.is_empty() {
+            "Empty".to_string()
+        } else {
+            self.player.item_labels().join(", ")
+        }
+    }
+
+    /// Get the settings the game was started with
+    pub fn config(&self) -> &GameConfig {
+        &self.config
+    }
+
+    /// Get the greeting shown when the game starts
+    pub fn welcome_message(&self) -> String {
+        format!(
+            "Welcome to the Forgotten Temple! Difficulty: {}. Type 'help' for commands.\n\n{}",
+            self.config.difficulty.name(),
+            self.intro_text()
+        )
+    }
+
+    /// Get the story that sets up the game, shown at the start and by the 'intro' command
+    pub fn intro_text(&self) -> String {
+        "You are an explorer who has ventured deep into a newly discovered ancient temple. \
+        While you were examining the inner chambers, a sudden tremor shook the ground and a cave-in \
+        sealed the entrance behind you. You must find another way out of this forgotten temple \
+        before it becomes your tomb.".to_string()
+    }
+
+    /// Get the items the player is carrying
+    pub fn get_inventory(&self) -> Vec<String> {
+        self.player.inventory.clone()
+    }
+
+    /// Get the items the player is carrying in alphabetical order
+    pub fn get_sorted_inventory(&self) -> Vec<String> {
+        self.player.sorted_inventory()
+    }
+
+    /// Get the directions the player can see a way out of the current room
+    pub fn get_room_exits(&self) -> Vec<Direction> {
+        if let Some(room) = self.rooms.get(&self.player.location) {
+            room.visible_exits().map(|(direction, _)| direction.clone()).collect()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Get the items in the current room that can be picked up
+    pub fn get_room_items(&self) -> Vec<String> {
+        if let Some(room) = self.rooms.get(&self.player.location) {
+            room.items.iter().filter(|item| !room.is_fixed(item)).cloned().collect()
+        } else {
+            Vec::new()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::Command;
+    use std::cell::RefCell;
+    use crate::storage::MemoryStorage;
+
+    #[test]
+    fn test_game_initialization() {
+        let game = Game::new();
+        assert_eq!(game.player.location, "Entrance Hall");
+        assert_eq!(game.player.inventory.len(), 0);
+        assert!(!game.game_over);
+    }
+
+    #[test]
+    fn test_process_go_command() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert_eq!(game.player.location, "Ceremonial Antechamber");
+        assert!(result.contains("Ceremonial Antechamber"));
+
+        // Try an invalid direction
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert_eq!(game.player.location, "Ceremonial Antechamber"); // Location shouldn't change
+        assert!(result.contains("can't go"));
+    }
+
+    #[test]
+    fn test_take_item() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Take("ancient map".to_string())).message;
+        assert!(game.player.inventory.contains(&"ancient map".to_string()));
+        assert!(result.contains("You take"));
+
+        // Try taking a nonexistent item
+        let result = game.process_command(Command::Take("gold coin".to_string())).message;
+        assert!(!game.player.inventory.contains(&"gold coin".to_string()));
+        assert!(result.contains("There is no"));
+    }
+
+    #[test]
+    fn test_entrance_is_sealed() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Go(Direction::South)).message;
+        assert_eq!(result, "The cave-in has sealed the way you came. You must find another exit.");
+        assert_eq!(game.player.location, "Entrance Hall");
+    }
+
+    #[test]
+    fn test_locked_door_blocks_movement() {
+        let mut game = Game::new();
+        game.rooms.get_mut("Entrance Hall").unwrap().add_lock(Direction::North, "torch");
+
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert_eq!(result, "The way north is locked. You need a torch.");
+        assert_eq!(game.player.location, "Entrance Hall");
+    }
+
+    #[test]
+    fn test_locked_door_opens_with_key() {
+        let mut game = Game::new();
+        game.rooms.get_mut("Entrance Hall").unwrap().add_lock(Direction::North, "torch");
+        game.player.take_item("torch");
+
+        game.process_command(Command::Go(Direction::North));
+        assert_eq!(game.player.location, "Ceremonial Antechamber");
+        assert!(game.unlocked.contains(&("Entrance Hall".to_string(), Direction::North)));
+    }
+
+    #[test]
+    fn test_treasure_room_exit_hidden_until_idol_placed() {
+        let mut game = Game::new_with_start("Treasure Room", &["golden idol"]).unwrap();
+        assert!(!game.get_room_exits().contains(&Direction::North));
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert_eq!(result, "You can't go north from here.");
+
+        let result = game.process_command(Command::UseOn("golden idol".to_string(), "pedestal".to_string())).message;
+        assert!(result.contains("revealing a narrow passage"));
+        assert!(game.player.has_item("golden idol"));
+        assert!(game.get_room_exits().contains(&Direction::North));
+
+        let result = game.process_command(Command::Use("golden idol".to_string())).message;
+        assert_eq!(result, "The passage north already stands open.");
+        game.process_command(Command::Go(Direction::North));
+        assert_eq!(game.player.location, "Temple Exit");
+    }
+
+    #[test]
+    fn test_save_round_trip() {
+        let mut storage = MemoryStorage::new();
+        let mut game = Game::new();
+        game.process_command(Command::Take("ancient map".to_string()));
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Label("altar room".to_string()));
+        game.save(&mut storage, "slot1").unwrap();
+
+        let loaded = Game::load(&storage, "slot1").unwrap();
+        assert_eq!(loaded.player.location, "Ceremonial Antechamber");
+        assert!(loaded.player.has_item("ancient map"));
+        assert!(loaded.visited.contains("Entrance Hall"));
+        assert_eq!(loaded.player_labels.get("Ceremonial Antechamber"), Some(&"altar room".to_string()));
+        assert_eq!(loaded.turns(), game.turns());
+
+        assert!(Game::load(&storage, "missing").is_err());
+    }
+
+    #[test]
+    fn test_take_within_capacity() {
+        let mut game = Game::new();
+        game.process_command(Command::Go(Direction::North));
+        let result = game.process_command(Command::Take("ceremonial dagger".to_string())).message;
+        assert_eq!(result, "You take the ceremonial dagger.");
+        assert_eq!(game.player.current_weight(), 3);
+        assert!(game.player.display_inventory().contains("(3/10)"));
+    }
+
+    #[test]
+    fn test_take_over_capacity_is_rejected() {
+        let mut game = Game::new();
+        game.player.take_item("ceremonial dagger");
+        game.player.take_item("torch");
+        game.player.take_item("ancient map");
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Go(Direction::West));
+
+        let outputs = game.run_script(&["take golden idol", "no"]);
+        assert_eq!(outputs[0], "Your pack is full. Drop something to make room? (item name / no)");
+        assert_eq!(outputs[1], "You leave the golden idol where it is.");
+        assert!(!game.player.has_item("golden idol"));
+        assert!(game.get_room_items().contains(&"golden idol".to_string()));
+
+        // Nothing can be dropped to make room for something too heavy to ever carry
+        game.player.max_weight = 4;
+        let result = game.process_command(Command::Take("golden idol".to_string())).message;
+        assert_eq!(result, "The golden idol is too heavy to carry right now.");
+        game.player.max_weight = 10;
+
+        // Dropping something frees up enough room
+        game.process_command(Command::Drop("ceremonial dagger".to_string()));
+        let result = game.process_command(Command::Take("golden idol".to_string())).message;
+        assert!(result.starts_with("You take the golden idol."));
+    }
+
+    #[test]
+    fn test_full_pack_offers_to_swap() {
+        let mut game = Game::new_with_start(GUARDIAN_CHAMBER, &["ceremonial dagger", "torch", "ancient map"]).unwrap();
+        game.player.max_weight = 9;
+
+        // Dropping something too light to make room leaves the idol behind
+        let outputs = game.run_script(&["take golden idol", "map"]);
+        assert_eq!(outputs[1], "You drop the ancient map. Even so, the golden idol is still too heavy to carry.");
+        assert!(!game.player.has_item("golden idol"));
+
+        // The answer isn't taken for the command it would otherwise be
+        let outputs = game.run_script(&["take golden idol", "dagger", "look"]);
+        assert!(outputs[1].starts_with("You drop the ceremonial dagger. You take the golden idol."));
+        assert!(outputs[2].contains("[ Guardian Chamber ]"));
+        assert!(game.player.has_item("golden idol"));
+        assert!(game.get_room_items().contains(&"ceremonial dagger".to_string()));
+    }
+
+    #[test]
+    fn test_overloaded_player_cannot_move() {
+        let mut game = Game::new();
+        game.player.max_weight = 6;
+        game.player.take_item("golden idol");
+        game.player.take_item("torch");
+
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert_eq!(result, "You're overloaded and can't move. Drop something.");
+        assert_eq!(game.player.location, "Entrance Hall");
+
+        // Dropping the torch brings the player back under capacity
+        game.process_command(Command::Drop("torch".to_string()));
+        assert!(game.get_room_items().contains(&"torch".to_string()));
+        game.process_command(Command::Go(Direction::North));
+        assert_eq!(game.player.location, "Ceremonial Antechamber");
+    }
+
+    #[test]
+    fn test_world_file_round_trip_and_escape() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/worlds/forgotten_temple.json");
+        let world = WorldDef::from_file(path).unwrap();
+        assert_eq!(WorldDef::from_json(&world.to_json().unwrap()).unwrap(), world);
+
+        let mut game = Game::from_world_file(path).unwrap();
+        assert_eq!(game.player.location, "Entrance Hall");
+        for command in [
+            Command::Go(Direction::North),
+            Command::Go(Direction::West),
+            Command::Take("golden idol".to_string()),
+            Command::Go(Direction::East),
+            Command::Go(Direction::East),
+            Command::Use("golden idol".to_string()),
+            Command::Go(Direction::North),
+        ] {
+            game.process_command(command);
+        }
+        assert_eq!(game.player.location, "Temple Exit");
+
+        game.process_command(Command::Use("golden idol".to_string()));
+        assert!(game.is_game_over());
+    }
+
+    #[test]
+    fn test_missing_world_file() {
+        assert!(Game::from_world_file("no/such/world.json").is_err());
+    }
+
+    #[test]
+    fn test_map_shows_visited_rooms() {
+        let mut game = Game::new();
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Go(Direction::West));
+
+        let map = game.process_command(Command::Map).message;
+        assert!(map.contains("Entrance Hall"));
+        assert!(map.contains("@ - Guardian Chamber"));
+        assert!(map.contains("[@]"));
+        assert!(!map.contains("Temple Exit"));
+    }
+
+    #[test]
+    fn test_dead_ends_are_tagged_on_map() {
+        let mut game = Game::new();
+        assert_eq!(game.dead_ends(), vec!["Ancient Crypt".to_string(), "Guardian Chamber".to_string()]);
+
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Go(Direction::West));
+        let map = game.process_command(Command::Map).message;
+        assert!(map.contains("@ - Guardian Chamber (you are here) *"));
+        assert!(!map.contains("Ceremonial Antechamber *"));
+        assert!(map.ends_with("Rooms marked * are dead ends."));
+    }
+
+    #[test]
+    fn test_solution_map_requires_win() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::SolutionMap).message;
+        assert!(result.contains("No peeking"));
+
+        for command in [
+            Command::Go(Direction::North),
+            Command::Go(Direction::West),
+            Command::Take("golden idol".to_string()),
+            Command::Go(Direction::East),
+            Command::Go(Direction::East),
+            Command::Use("golden idol".to_string()),
+            Command::Go(Direction::North),
+            Command::Use("golden idol".to_string()),
+        ] {
+            game.process_command(command);
+        }
+
+        let result = game.process_command(Command::SolutionMap).message;
+        assert!(result.contains(
+            "Fastest route: Entrance Hall -> Ceremonial Antechamber -> Treasure Room -> Temple Exit"
+        ));
+        assert!(result.contains("*@*"));
+    }
+
+    #[test]
+    fn test_label_room() {
+        let mut game = Game::new();
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Label("altar room".to_string()));
+
+        let result = game.process_command(Command::Look).message;
+        assert!(result.contains("Your note: \"altar room\""));
+        assert!(game.render_map().contains("Ceremonial Antechamber \"altar room\""));
+
+        // Other rooms don't show the label
+        let result = game.process_command(Command::Go(Direction::South)).message;
+        assert!(!result.contains("altar room"));
+    }
+
+    #[test]
+    fn test_command_result_flags_room_change() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Go(Direction::North));
+        assert!(result.room_changed);
+        assert_eq!(result.new_room, Some("Ceremonial Antechamber".to_string()));
+        assert!(!result.game_over);
+
+        let result = game.process_command(Command::Look);
+        assert!(!result.room_changed);
+        assert_eq!(result.new_room, None);
+
+        game.process_command(Command::Quit);
+        let result = game.process_command(Command::Yes);
+        assert!(result.game_over);
+        assert!(!result.won);
+    }
+
+    #[test]
+    fn test_command_result_flags_items_and_wins() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Take("ancient map".to_string()));
+        assert_eq!(result.item_taken, Some("ancient map".to_string()));
+        assert_eq!(result.to_string(), "You take the ancient map.");
+
+        let result = game.process_command(Command::Take("golden idol".to_string()));
+        assert_eq!(result.item_taken, None);
+        assert!(result.items_taken.is_empty());
+
+        game.player.take_item("golden idol");
+        game.player.location = "Temple Exit".to_string();
+        let result = game.process_command(Command::Use("golden idol".to_string()));
+        assert!(result.won);
+        assert!(result.game_over);
+    }
+
+    #[test]
+    fn test_moves_count_down_turns_remaining() {
+        let mut game = Game::new();
+        assert_eq!(game.turns_remaining(), DEFAULT_TURN_LIMIT);
+
+        game.process_command(Command::Go(Direction::North));
+        assert_eq!(game.turns_remaining(), DEFAULT_TURN_LIMIT - 1);
+
+        // Failed moves and other commands don't use up time
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Look);
+        assert_eq!(game.turns_remaining(), DEFAULT_TURN_LIMIT - 1);
+        assert!(!game.process_command(Command::Look).message.contains("trembles"));
+        assert!(!game.is_game_over());
+    }
+
+    #[test]
+    fn test_temple_collapses_at_turn_limit() {
+        let mut game = Game::with_turn_limit(3);
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert!(result.contains("2 moves before collapse!"));
+
+        game.process_command(Command::Go(Direction::South));
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert!(result.contains("ceiling comes crashing down"));
+        assert!(game.is_game_over());
+        assert!(!game.won);
+    }
+
+    #[test]
+    fn test_hint_points_to_idol() {
+        let game = Game::new();
+        assert_eq!(
+            game.hint(),
+            "You'll need the golden idol to escape. Try heading north towards the Guardian Chamber."
+        );
+    }
+
+    #[test]
+    fn test_hint_points_to_exit_with_idol() {
+        let mut game = Game::new();
+        game.player.take_item("golden idol");
+        game.player.location = "Guardian Chamber".to_string();
+        assert_eq!(game.hint(), "You have what you need. Head east towards the Temple Exit.");
+    }
+
+    #[test]
+    fn test_hint_at_exit_with_idol() {
+        let mut game = Game::new();
+        game.player.take_item("golden idol");
+        game.player.location = "Temple Exit".to_string();
+        assert_eq!(game.hint(), "This is the way out. Try using the golden idol.");
+    }
+
+    #[test]
+    fn test_score_rewards_items_and_costs_moves() {
+        let mut game = Game::new();
+        game.process_command(Command::Go(Direction::North));
+        assert_eq!(game.score(), -MOVE_PENALTY);
+
+        game.process_command(Command::Go(Direction::West));
+        game.process_command(Command::Take("golden idol".to_string()));
+        assert_eq!(game.score(), score::item_points("golden idol") - 2 * MOVE_PENALTY);
+
+        // Dropping and retaking an item doesn't score twice
+        game.process_command(Command::Drop("golden idol".to_string()));
+        game.process_command(Command::Yes);
+        game.process_command(Command::Take("golden idol".to_string()));
+        assert_eq!(game.score(), score::item_points("golden idol") - 2 * MOVE_PENALTY);
+        assert!(game.score_breakdown().contains("Movement: -2"));
+    }
+
+    #[test]
+    fn test_final_score_on_escape() {
+        let mut game = Game::new();
+        game.player.take_item("golden idol");
+        game.player.location = "Temple Exit".to_string();
+        let result = game.process_command(Command::Use("golden idol".to_string())).message;
+        assert_eq!(game.score(), ESCAPE_BONUS);
+        assert!(result.ends_with(&format!("Final score: {}", ESCAPE_BONUS)));
+    }
+
+    #[test]
+    fn test_ui_accessors() {
+        let mut game = Game::new();
+        assert_eq!(game.get_current_room().map(|room| room.name.as_str()), Some("Entrance Hall"));
+        assert!(game.get_current_room_description().starts_with("Entrance Hall\n"));
+        assert_eq!(game.get_room_items(), vec!["ancient map"]);
+        assert_eq!(game.get_inventory_display(), "Empty");
+
+        game.process_command(Command::Take("ancient map".to_string()));
+        assert_eq!(game.get_inventory_display(), "ancient map");
+        assert!(game.get_room_items().is_empty());
+
+        // A missing room doesn't panic
+        game.player.location = "Nowhere".to_string();
+        assert!(game.get_current_room().is_none());
+        assert!(game.get_current_room_description().contains("Nowhere"));
+        assert!(game.get_room_items().is_empty());
+    }
+
+    #[test]
+    fn test_canonicalize_item() {
+        assert_eq!(canonicalize_item("map"), "ancient map");
+        assert_eq!(canonicalize_item("ancient map"), "ancient map");
+        assert_eq!(canonicalize_item("Idol"), "golden idol");
+        assert_eq!(canonicalize_item("torch"), "torch");
+    }
+
+    #[test]
+    fn test_take_and_use_by_alias() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Take("map".to_string())).message;
+        assert_eq!(result, "You take the ancient map.");
+
+        let result = game.process_command(Command::Use("map".to_string())).message;
+        assert!(result.contains("You examine the ancient map"));
+    }
+
+    #[test]
+    fn test_combine_items() {
+        let mut game = Game::new();
+        game.player.take_item("silver amulet");
+        game.player.take_item("jade scarab");
+
+        let result = game.process_command(Command::Combine("jade scarab".to_string(), "silver amulet".to_string()));
+        assert_eq!(result.message, "You combine the jade scarab and the silver amulet into a scarab amulet.");
+        assert_eq!(game.player.inventory, vec!["scarab amulet"]);
+    }
+
+    #[test]
+    fn test_combine_needs_both_ingredients() {
+        let mut game = Game::new();
+        game.player.take_item("silver amulet");
+
+        let result = game.process_command(Command::Combine("silver amulet".to_string(), "jade scarab".to_string()));
+        assert_eq!(result.message, "You don't have a jade scarab.");
+        assert_eq!(game.player.inventory, vec!["silver amulet"]);
+    }
+
+    #[test]
+    fn test_combine_unknown_recipe() {
+        let mut game = Game::new();
+        game.player.take_item("torch");
+        game.player.take_item("ancient map");
+
+        let result = game.process_command(Command::Combine("torch".to_string(), "ancient map".to_string()));
+        assert_eq!(result.message, "Those items can't be combined.");
+        assert_eq!(game.player.inventory.len(), 2);
+    }
+
+    #[test]
+    fn test_open_sarcophagus_and_take() {
+        let mut game = Game::new();
+        game.player.location = "Ancient Crypt".to_string();
+
+        // The ring can't be reached while the lid is closed
+        let result = game.process_command(Command::Take("obsidian ring".to_string())).message;
+        assert_eq!(result, "There is no obsidian ring here.");
+
+        let result = game.process_command(Command::Open("sarcophagus".to_string())).message;
+        assert_eq!(result, "You open the sarcophagus. Inside you find: obsidian ring.");
+        assert!(game.look_around().contains("The sarcophagus lies open. Inside: obsidian ring."));
+        let result = game.process_command(Command::Open("sarcophagus".to_string())).message;
+        assert_eq!(result, "The sarcophagus is already open.");
+
+        let result = game.process_command(Command::Take("obsidian ring".to_string())).message;
+        assert_eq!(result, "You take the obsidian ring.");
+        assert!(game.look_around().contains("The sarcophagus lies open and empty."));
+    }
+
+    #[test]
+    fn test_closing_container_hides_contents() {
+        let mut game = Game::new();
+        game.player.location = "Ancient Crypt".to_string();
+        let result = game.process_command(Command::Close("sarcophagus".to_string())).message;
+        assert_eq!(result, "The sarcophagus is already closed.");
+
+        game.process_command(Command::Open("sarcophagus".to_string()));
+        let result = game.process_command(Command::Close("sarcophagus".to_string())).message;
+        assert_eq!(result, "You close the sarcophagus.");
+        assert!(!game.look_around().contains("obsidian ring"));
+        let result = game.process_command(Command::Take("obsidian ring".to_string())).message;
+        assert_eq!(result, "There is no obsidian ring here.");
+
+        let result = game.process_command(Command::Open("chest".to_string())).message;
+        assert_eq!(result, "There's no chest here to open.");
+    }
+
+    #[test]
+    fn test_go_back() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Back).message;
+        assert_eq!(result, "You can't go back from here.");
+
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Back);
+        assert_eq!(game.player.location, "Entrance Hall");
+
+        // Going back again toggles between the two rooms
+        game.process_command(Command::Back);
+        assert_eq!(game.player.location, "Ceremonial Antechamber");
+    }
+
+    #[test]
+    fn test_go_to_named_room() {
+        let mut game = Game::new();
+        assert_eq!(game.resolve_named_exit("Crypt"), Some(Direction::East));
+        assert_eq!(game.resolve_named_exit("guardian"), None);
+
+        let result = game.process_command(Command::GoNamed("guardian".to_string())).message;
+        assert_eq!(result, "There's no way to the guardian from here.");
+
+        game.process_command(Command::GoNamed("crypt".to_string()));
+        assert_eq!(game.player.location, "Ancient Crypt");
+    }
+
+    #[test]
+    fn test_reset_returns_to_initial_state() {
+        let mut game = Game::with_turn_limit(30);
+        for command in [
+            Command::Take("ancient map".to_string()),
+            Command::Go(Direction::North),
+            Command::Go(Direction::West),
+            Command::Take("golden idol".to_string()),
+            Command::Label("idol room".to_string()),
+            Command::Talk("guardian".to_string()),
+        ] {
+            game.process_command(command);
+        }
+
+        let result = game.process_command(Command::Restart);
+        assert!(result.message.contains("[ Entrance Hall ]"));
+        assert!(!result.game_over);
+
+        let fresh = Game::with_turn_limit(30);
+        assert_eq!(game.player, fresh.player);
+        assert_eq!(game.rooms, fresh.rooms);
+        assert_eq!(game.visited, fresh.visited);
+        assert_eq!(game.flags, fresh.flags);
+        assert_eq!(game.turns(), 0);
+        assert_eq!(game.turns_remaining(), 30);
+        assert_eq!(game.score(), 0);
+        assert!(game.player_labels.is_empty());
+        assert!(game.dialogue_progress.is_empty());
+
+        // An accidental restart can be taken back
+        game.process_command(Command::Undo);
+        assert!(game.player.has_item("golden idol"));
+    }
+
+    #[test]
+    fn test_clone_is_independent_of_the_original() {
+        let mut game = Game::new();
+        game.process_command(Command::Take("ancient map".to_string()));
+
+        let mut copy = game.clone();
+        copy.process_command(Command::Go(Direction::North));
+        copy.process_command(Command::Drop("ancient map".to_string()));
+
+        assert_eq!(copy.player.location, "Ceremonial Antechamber");
+        assert_eq!(game.player.location, "Entrance Hall");
+        assert!(game.player.has_item("ancient map"));
+        assert!(!game.rooms["Ceremonial Antechamber"].items.iter().any(|item| item == "ancient map"));
+        assert!(game.turns_remaining() > copy.turns_remaining());
+    }
+
+    #[test]
+    fn test_solve_finds_a_winning_route() {
+        let game = Game::new();
+        let solution = game.solve(12).expect("the temple should be solvable");
+        assert!(solution.len() <= 12);
+
+        let mut replay = Game::new();
+        let lines: Vec<&str> = solution.iter().map(|line| line.as_str()).collect();
+        let outputs = replay.run_script(&lines);
+        assert!(replay.won);
+        assert!(outputs.last().unwrap().contains("escaped the forgotten temple"));
+
+        // Too short a search finds nothing, and the original game is untouched
+        assert_eq!(game.solve(2), None);
+        assert_eq!(game.player.location, "Entrance Hall");
+        assert_eq!(game.turns, 0);
+    }
+
+    #[test]
+    fn test_required_item_is_never_used_up() {
+        let mut game = Game::new();
+        game.player.take_item("golden idol");
+        game.player.uses_remaining.insert("golden idol".to_string(), 1);
+        game.player.location = "Treasure Room".to_string();
+
+        let result = game.process_command(Command::Use("golden idol".to_string())).message;
+        assert!(!result.contains("dies"));
+        assert!(game.player.has_item("golden idol"));
+
+        game.process_command(Command::Go(Direction::North));
+        let result = game.process_command(Command::Use("golden idol".to_string())).message;
+        assert!(result.contains("escaped the forgotten temple"));
+    }
+
+    #[test]
+    fn test_exit_suggests_a_way_back_when_the_required_item_is_gone() {
+        let mut game = Game::new();
+        game.rooms.get_mut("Guardian Chamber").unwrap().items.retain(|item| item != "golden idol");
+        game.rooms.get_mut("Treasure Room").unwrap().reveal_exit(&Direction::North);
+        game.player.location = "Treasure Room".to_string();
+
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert!(result.contains("no longer any way to get one"));
+        assert!(result.contains("'restart'"));
+    }
+
+    #[test]
+    fn test_verb_table_is_used_for_typed_input() {
+        let mut verbs = VerbTable::default();
+        verbs.register("grab", crate::input::CommandKind::Take);
+        verbs.set_correct_directions(true);
+        let mut game = Game::new().with_verbs(verbs);
+
+        let outputs = game.run_script(&["grab ancient map", "go norht"]);
+        assert_eq!(outputs[0], "You take the ancient map.");
+        assert!(outputs[1].contains("[ Ceremonial Antechamber ]"));
+        assert_eq!(game.parse_input("grab torch then go est").len(), 2);
+
+        // The verbs survive a restart
+        game.reset();
+        assert_eq!(game.parse_line("grab torch"), Ok(Command::Take("torch".to_string())));
+    }
+
+    #[test]
+    fn test_undo_takes_back_achievements_and_labels() {
+        let mut game = Game::new();
+        game.process_command(Command::Take("ancient map".to_string()));
+        assert!(game.achievements.contains(&Achievement::FirstItem));
+        game.process_command(Command::Undo);
+        assert!(game.achievements.is_empty());
+
+        game.process_command(Command::Label("start".to_string()));
+        game.process_command(Command::Undo);
+        assert!(game.player_labels.is_empty());
+    }
+
+    #[test]
+    fn test_undo_move() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Undo).message;
+        assert_eq!(result, "Nothing to undo.");
+
+        game.process_command(Command::Go(Direction::North));
+        let result = game.process_command(Command::Undo).message;
+        assert_eq!(result, "Undone.");
+        assert_eq!(game.player.location, "Entrance Hall");
+        assert_eq!(game.turns_remaining(), DEFAULT_TURN_LIMIT);
+        assert_eq!(game.process_command(Command::Undo).message, "Nothing to undo.");
+    }
+
+    #[test]
+    fn test_undo_take() {
+        let mut game = Game::new();
+        game.process_command(Command::Take("ancient map".to_string()));
+        // Looking around changes nothing, so it isn't undone
+        game.process_command(Command::Look);
+
+        game.process_command(Command::Undo);
+        assert!(!game.player.has_item("ancient map"));
+        assert!(game.rooms["Entrance Hall"].has_item("ancient map"));
+        assert_eq!(game.score(), 0);
+    }
+
+    #[test]
+    fn test_undo_history_is_capped() {
+        let mut game = Game::with_turn_limit(100);
+        for _ in 0..MAX_UNDO_HISTORY {
+            game.process_command(Command::Go(Direction::East));
+            game.process_command(Command::Go(Direction::West));
+        }
+        assert_eq!(game.history.len(), MAX_UNDO_HISTORY);
+    }
+
+    #[test]
+    fn test_read_pedestal_inscription() {
+        let mut game = Game::new();
+        game.player.location = "Treasure Room".to_string();
+        let result = game.process_command(Command::Read("inscription".to_string())).message;
+        assert_eq!(result, "The inscription reads: 'Place the sacred idol to reveal the path.'");
+
+        let result = game.process_command(Command::Read("pedestal".to_string())).message;
+        assert_eq!(result, "There's nothing like that to read here.");
+    }
+
+    #[test]
+    fn test_crypt_inscriptions_need_light() {
+        let mut game = Game::new();
+        game.player.take_item("torch");
+        game.player.location = "Ancient Crypt".to_string();
+
+        let result = game.process_command(Command::Read("inscriptions".to_string())).message;
+        assert_eq!(result, "It's too dark in here to read anything.");
+
+        game.process_command(Command::Use("torch".to_string()));
+        let result = game.process_command(Command::Read("inscriptions".to_string())).message;
+        assert!(result.contains("The stars remember"));
+    }
+
+    #[test]
+    fn test_true_escape_ending() {
+        let mut game = Game::new();
+        game.player.take_item("golden idol");
+        game.player.location = "Temple Exit".to_string();
+        let result = game.process_command(Command::Use("golden idol".to_string())).message;
+        assert!(game.is_game_over());
+        assert_eq!(game.ending(), Some(Ending::TrueEscape));
+        assert!(result.contains("Ending: true escape"));
+    }
+
+    #[test]
+    fn test_narrow_escape_through_star_map() {
+        let mut game = Game::new();
+        game.player.take_item("torch");
+        game.player.location = "Ancient Crypt".to_string();
+
+        let result = game.process_command(Command::Read("star map".to_string())).message;
+        assert_eq!(result, "It's too dark in here to make out the stars.");
+
+        game.process_command(Command::Use("torch".to_string()));
+        let result = game.process_command(Command::Read("star map".to_string())).message;
+        assert!(result.contains("narrow passage"));
+
+        let result = game.process_command(Command::Go(Direction::East)).message;
+        assert!(game.is_game_over());
+        assert_eq!(game.ending(), Some(Ending::NarrowEscape));
+        assert!(result.contains("Ending: narrow escape"));
+        assert!(!result.contains("true escape"));
+        assert_eq!(game.score(), NARROW_ESCAPE_BONUS - MOVE_PENALTY);
+    }
+
+    #[test]
+    fn test_burned_star_map_cannot_be_read() {
+        let mut game = Game::new();
+        game.player.take_item("torch");
+        game.player.location = "Ancient Crypt".to_string();
+        game.process_command(Command::Use("torch".to_string()));
+        game.process_command(Command::UseOn("torch".to_string(), "tapestry".to_string()));
+
+        let result = game.process_command(Command::Read("star map".to_string())).message;
+        assert_eq!(result, "Only ashes remain where the tapestry once hung.");
+        assert!(!game.rooms.contains_key(SECRET_PASSAGE));
+    }
+
+    #[test]
+    fn test_talk_to_guardian() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Talk("guardian".to_string())).message;
+        assert_eq!(result, "There's no one here called guardian.");
+
+        game.player.location = "Guardian Chamber".to_string();
+        assert!(game.look_around().contains("The guardian is here."));
+
+        let result = game.process_command(Command::Talk("Guardian".to_string())).message;
+        assert!(result.starts_with("The guardian says: \"Few have entered this temple"));
+
+        // Dialogue advances, then repeats the final line
+        let second = game.process_command(Command::Talk("guardian".to_string())).message;
+        assert_ne!(second, result);
+        let third = game.process_command(Command::Talk("guardian".to_string())).message;
+        let fourth = game.process_command(Command::Talk("guardian".to_string())).message;
+        assert_eq!(third, fourth);
+    }
+
+    #[test]
+    fn test_read_ancient_map() {
+        let mut game = Game::new();
+        game.player.location = "Guardian Chamber".to_string();
+        let result = game.process_command(Command::Read("ancient map".to_string())).message;
+        assert_eq!(result, "There's nothing like that to read here.");
+        game.player.location = "Entrance Hall".to_string();
+
+        game.process_command(Command::Take("ancient map".to_string()));
+        let result = game.process_command(Command::Read("ancient map".to_string())).message;
+        assert!(result.lines().count() > 3);
+        assert!(result.contains("[Guardian Chamber]"));
+        assert!(result.contains("[Temple Exit]"));
+
+        // The diagram doesn't depend on where the player has been
+        assert!(!game.visited.contains("Guardian Chamber"));
+    }
+
+    #[test]
+    fn test_burning_tapestry_reveals_alcove() {
+        let mut game = Game::new();
+        game.process_command(Command::Go(Direction::East));
+        game.process_command(Command::Take("torch".to_string()));
+
+        // The torch has to be lit first
+        let result = game.process_command(Command::UseOn("torch".to_string(), "tapestry".to_string())).message;
+        assert!(result.contains("isn't lit"));
+        assert!(game.flags.contains(FLAG_STAR_CLUE));
+
+        game.process_command(Command::Use("torch".to_string()));
+        let result = game.process_command(Command::UseOn("torch".to_string(), "tapestry".to_string())).message;
+        assert!(result.contains("alcove"));
+        assert!(!game.flags.contains(FLAG_STAR_CLUE));
+        assert!(game.get_room_items().contains(&"jade scarab".to_string()));
+    }
+
+    #[test]
+    fn test_search_finds_hidden_item_in_normal_mode() {
+        let mut game = Game::with_seed(Difficulty::Normal, 1);
+        game.process_command(Command::Go(Direction::East));
+        let turns = game.turns();
+
+        let result = game.process_command(Command::Search).message;
+        assert!(result.contains("silver amulet"));
+        assert!(game.get_room_items().contains(&"silver amulet".to_string()));
+        assert_eq!(game.turns(), turns + 1);
+    }
+
+    #[test]
+    fn test_search_springs_trap_in_hard_mode() {
+        // This seed's first roll falls within the trap chance
+        let mut game = Game::with_seed(Difficulty::Hard, 2);
+        game.process_command(Command::Go(Direction::East));
+
+        let result = game.process_command(Command::Search).message;
+        assert!(result.contains("trap"));
+        assert!(!game.get_room_items().contains(&"silver amulet".to_string()));
+
+        // The trap is spent, so searching again is safe
+        let result = game.process_command(Command::Search).message;
+        assert!(result.contains("silver amulet"));
+    }
+
+    #[test]
+    fn test_search_behind_pedestal() {
+        let mut game = Game::new();
+        game.player.location = "Treasure Room".to_string();
+
+        let result = game.process_command(Command::Search).message;
+        assert_eq!(result, "You search carefully and find: gold amulet");
+
+        // Searching again finds nothing and doesn't duplicate the amulet
+        let result = game.process_command(Command::Search).message;
+        assert_eq!(result, "You find nothing new.");
+        assert_eq!(game.get_room_items().iter().filter(|i| *i == "gold amulet").count(), 1);
+    }
+
+    #[test]
+    fn test_search_empty_room() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Search).message;
+        assert_eq!(result, "You find nothing new.");
+    }
+
+    #[test]
+    fn test_hard_has_fewer_turns_than_normal() {
+        let normal = GameConfig::for_difficulty(Difficulty::Normal);
+        let hard = GameConfig::for_difficulty(Difficulty::Hard);
+        assert!(hard.turn_limit < normal.turn_limit);
+        assert!(hard.max_weight < normal.max_weight);
+
+        let game = Game::with_config(hard.clone());
+        assert_eq!(game.turns_remaining(), hard.turn_limit);
+        assert_eq!(game.player.max_weight, hard.max_weight);
+        assert_eq!(Game::new().config(), &normal);
+    }
+
+    #[test]
+    fn test_hints_disabled_on_hard() {
+        let mut game = Game::with_config(GameConfig::for_difficulty(Difficulty::Hard));
+        let hint = game.process_command(Command::Hint).message;
+        assert!(hint.contains("Hints are not available on Hard difficulty"));
+
+        let mut game = Game::with_config(GameConfig::for_difficulty(Difficulty::Easy));
+        assert!(!game.process_command(Command::Hint).message.contains("not available"));
+    }
+
+    #[test]
+    fn test_intro_text() {
+        let mut game = Game::new();
+        let intro = game.process_command(Command::Intro).message;
+        assert!(!intro.is_empty());
+        assert!(intro.contains("forgotten temple"));
+        assert!(game.welcome_message().ends_with(&intro));
+    }
+
+    #[test]
+    fn test_welcome_message_shows_difficulty() {
+        let game = Game::with_config(GameConfig::for_difficulty(Difficulty::Easy));
+        assert!(game.welcome_message().contains("Difficulty: Easy"));
+    }
+
+    #[test]
+    fn test_list_exits_masks_unvisited_rooms() {
+        let mut game = Game::new();
+        assert_eq!(game.list_exits(), vec!["north -> ???", "east -> ???"]);
+
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Go(Direction::South));
+        assert_eq!(game.list_exits(), vec!["north -> Ceremonial Antechamber", "east -> ???"]);
+        assert!(game.process_command(Command::Exits).message.contains("north -> Ceremonial Antechamber"));
+    }
+
+    #[test]
+    fn test_new_seeded_places_items_deterministically() {
+        let game = Game::new_seeded(42);
+        let same = Game::new_seeded(42);
+        assert!(game.rooms == same.rooms);
+
+        for seed in 0..20 {
+            let game = Game::new_seeded(seed);
+            let placed: Vec<&String> = game.rooms.values().flat_map(|room| room.items.iter()).collect();
+            for item in SHUFFLED_ITEMS {
+                assert_eq!(placed.iter().filter(|placed| placed.as_str() == *item).count(), 1);
+            }
+
+            // The idol stays in the Guardian Chamber, which can always be reached
+            assert!(game.rooms["Guardian Chamber"].items.contains(&"golden idol".to_string()));
+            assert!(map::shortest_path(&game.rooms, "Entrance Hall", "Guardian Chamber").is_some());
+        }
+    }
+
+    #[test]
+    fn test_throw_dagger_disarms_statue() {
+        let mut game = Game::new();
+        game.player.location = GUARDIAN_CHAMBER.to_string();
+        game.player.take_item("ceremonial dagger");
+
+        let result = game.process_command(Command::Throw("dagger".to_string(), Some("statue".to_string()))).message;
+        assert!(result.contains("arms shudder to a halt"));
+        assert!(game.statue_disarmed);
+        assert!(!game.player.has_item("ceremonial dagger"));
+        assert!(game.get_room_items().contains(&"ceremonial dagger".to_string()));
+
+        let turns_remaining = game.turns_remaining();
+        let result = game.process_command(Command::Take("golden idol".to_string())).message;
+        assert_eq!(result, "You take the golden idol.");
+        assert_eq!(game.turns_remaining(), turns_remaining);
+    }
+
+    #[test]
+    fn test_search_and_examine_bring_the_collapse_closer() {
+        let mut game = Game::new();
+        game.process_command(Command::Search);
+        game.process_command(Command::Examine);
+        assert_eq!(game.turns(), 2);
+        assert_eq!(game.turns_remaining(), DEFAULT_TURN_LIMIT - 2);
+
+        game.turns_remaining = 1;
+        let result = game.process_command(Command::Search).message;
+        assert!(result.contains("The forgotten temple has become your tomb."));
+        assert!(game.is_game_over());
+    }
+
+    #[test]
+    fn test_taking_guarded_idol_costs_a_turn() {
+        let mut game = Game::new();
+        game.player.location = GUARDIAN_CHAMBER.to_string();
+
+        let turns_remaining = game.turns_remaining();
+        let result = game.process_command(Command::Take("golden idol".to_string())).message;
+        assert!(result.contains("statue's arms sweep down"));
+        assert!(game.player.has_item("golden idol"));
+        assert_eq!(game.turns_remaining(), turns_remaining - 1);
+    }
+
+    #[test]
+    fn test_throw_must_be_aimed_at_the_statue() {
+        let mut game = Game::new();
+        game.player.location = GUARDIAN_CHAMBER.to_string();
+        game.player.take_item("ceremonial dagger");
+
+        for target in [None, Some("wall".to_string())] {
+            let result = game.process_command(Command::Throw("dagger".to_string(), target)).message;
+            assert_eq!(result, "You throw the ceremonial dagger. It clatters to the floor.");
+            assert!(!game.statue_disarmed);
+            game.process_command(Command::Take("ceremonial dagger".to_string()));
+        }
+    }
+
+    #[test]
+    fn test_drop_all_asks_before_dropping_the_idol() {
+        let mut game = Game::new();
+        game.player.take_item("golden idol");
+        game.player.take_item("torch");
+
+        let result = game.process_command(Command::DropAll).message;
+        assert_eq!(result, "You drop: torch. The golden idol seems important. Drop it anyway? (y/n)");
+        assert!(game.player.has_item("golden idol"));
+        assert!(!game.player.has_item("torch"));
+
+        let result = game.process_command(Command::Yes).message;
+        assert_eq!(result, "You drop the golden idol.");
+        assert!(game.get_room_items().contains(&"golden idol".to_string()));
+    }
+
+    #[test]
+    fn test_idol_cant_be_thrown() {
+        let mut game = Game::new();
+        game.player.take_item("golden idol");
+
+        let result = game.process_command(Command::Throw("golden idol".to_string(), None)).message;
+        assert_eq!(result, "The golden idol seems far too important to throw around.");
+        assert!(game.player.has_item("golden idol"));
+    }
+
+    #[test]
+    fn test_throw_requires_item() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Throw("torch".to_string(), None)).message;
+        assert_eq!(result, "You don't have a torch.");
+    }
+
+    #[test]
+    fn test_take_uses_canonical_item_name() {
+        let mut game = Game::new();
+        game.rooms.get_mut("Entrance Hall").unwrap().add_item("Jade Mask");
+        let result = game.process_command(Command::Take("JADE mask".to_string())).message;
+        assert_eq!(result, "You take the Jade Mask.");
+        assert_eq!(game.player.inventory, vec!["Jade Mask".to_string()]);
+    }
+
+    #[test]
+    fn test_look_direction_peeks_without_moving() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::LookDirection(Direction::North)).message;
+        assert!(result.starts_with("To the north lies the Ceremonial Antechamber. "));
+        assert!(result.ends_with('.'));
+        assert_eq!(game.player.location, "Entrance Hall");
+
+        // The caved-in way back is not an exit
+        let result = game.process_command(Command::LookDirection(Direction::South)).message;
+        assert_eq!(result, "There's nothing that way.");
+    }
+
+    #[test]
+    fn test_record_score_after_escaping() {
+        let storage = Rc::new(RefCell::new(MemoryStorage::new()));
+        let mut game = Game::new().with_highscores(storage.clone());
+        assert!(game.record_score("early").is_err());
+
+        game.run_script(&["go north", "go west", "take golden idol", "go east", "go east", "place golden idol", "go north", "use golden idol"]);
+        game.record_score("explorer").unwrap();
+        let table = game.process_command(Command::Scores).message;
+        assert!(table.contains(&format!("explorer - {} points in {} moves", game.score(), game.turns())));
+
+        assert!(storage.borrow().read(highscores::HIGHSCORES_KEY).is_some());
+
+        // Restarting keeps the table
+        game.reset();
+        assert!(game.keeps_highscores());
+    }
+
+    #[test]
+    fn test_entry_event_fires_once() {
+        let mut game = Game::new();
+        game.process_command(Command::Go(Direction::North));
+        let first = game.process_command(Command::Go(Direction::East)).message;
+        assert!(first.contains("[ Treasure Room ]"));
+        assert!(first.ends_with("The temple knows you are here."));
+
+        game.process_command(Command::Go(Direction::West));
+        let second = game.process_command(Command::Go(Direction::East)).message;
+        assert!(second.contains("[ Treasure Room ]"));
+        assert!(!second.contains("The temple knows you are here."));
+    }
+
+    #[test]
+    fn test_entry_event_can_spawn_item() {
+        let mut game = Game::new();
+        game.rooms.get_mut("Ceremonial Antechamber").unwrap().set_on_enter("A coin rolls out of the dark.", Some("gold coin"));
+        game.process_command(Command::Go(Direction::North));
+        assert!(game.get_room_items().contains(&"gold coin".to_string()));
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::input::{Command, parse_command};
+    #[test]
+    fn test_inventory_counts_stacked_items() {
+        let mut game = Game::new();
+        game.player.location = "Treasure Room".to_string();
+        game.process_command(Command::Take("gold coin".to_string()));
+        game.process_command(Command::Take("gold coin".to_string()));
+
+        assert!(game.player.display_inventory().contains("- gold coin x2\n"));
+        assert_eq!(game.get_inventory_display(), "gold coin x2");
+        assert!(game.player.has_item("gold coin"));
+
+        game.process_command(Command::Drop("gold coin".to_string()));
+        assert!(game.player.display_inventory().contains("- gold coin\n"));
+    }
 
     #[test]
-    fn test_game_initialization() {
-        let game = Game::new();
-        assert_eq!(game.player.location, "Entrance Hall");
-        assert_eq!(game.player.inventory.len(), 0);
-        assert_eq!(game.game_over, false);
+    fn test_goto_walks_through_visited_rooms() {
+        let mut game = Game::new();
+        assert_eq!(game.path_to("Ceremonial Antechamber"), None);
+        let result = game.process_command(Command::GoTo("guardian chamber".to_string())).message;
+        assert_eq!(result, "You don't know a way there yet.");
+
+        game.run_script(&["go north", "go west", "go east", "go south"]);
+        assert_eq!(game.path_to("ceremonial antechamber"), Some(vec![Direction::North]));
+        assert_eq!(game.path_to("Guardian Chamber"), Some(vec![Direction::North, Direction::West]));
+
+        let result = game.process_command(Command::GoTo("guardian chamber".to_string())).message;
+        assert!(result.contains("[ Guardian Chamber ]"));
+        assert_eq!(game.player.location, "Guardian Chamber");
     }
 
     #[test]
-    fn test_process_go_command() {
+    fn test_goto_stops_at_locked_door() {
         let mut game = Game::new();
-        let result = game.process_command(Command::Go(Direction::North));
+        game.rooms.get_mut("Ceremonial Antechamber").unwrap().add_lock(Direction::East, "torch");
+        game.visited.extend(["Ceremonial Antechamber", "Treasure Room"].map(String::from));
+        let result = game.process_command(Command::GoTo("treasure room".to_string())).message;
+        assert!(result.starts_with("You set off, but are stopped in the Ceremonial Antechamber: The way east is locked."));
         assert_eq!(game.player.location, "Ceremonial Antechamber");
-        assert!(result.contains("Ceremonial Antechamber"));
+    }
 
-        // Try an invalid direction
+    #[test]
+    fn test_quit_asks_for_confirmation() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::Quit);
+        assert_eq!(result.message, "Are you sure you want to quit? (y/n)");
+        assert!(!result.game_over);
+
+        let result = game.process_command(Command::Yes);
+        assert_eq!(result.message, "Thanks for playing! Goodbye.");
+        assert!(result.game_over);
+
+        // Asking twice also confirms
+        let mut game = Game::new();
+        game.process_command(Command::Quit);
+        assert!(game.process_command(Command::Quit).game_over);
+    }
+
+    #[test]
+    fn test_quit_can_be_cancelled() {
+        let mut game = Game::new();
+        game.process_command(Command::Quit);
         let result = game.process_command(Command::Go(Direction::North));
-        assert_eq!(game.player.location, "Ceremonial Antechamber"); // Location shouldn't change
-        assert!(result.contains("can't go"));
+        assert_eq!(result.message, "Quit cancelled.");
+        assert!(!result.game_over);
+        assert_eq!(game.player.location, "Entrance Hall");
+
+        // Once cancelled, commands run normally again
+        assert!(game.process_command(Command::Go(Direction::North)).room_changed);
     }
 
     #[test]
-    fn test_take_item() {
+    fn test_use_dagger_on_statue() {
         let mut game = Game::new();
-        let result = game.process_command(Command::Take("ancient map".to_string()));
-        assert!(game.player.inventory.contains(&"ancient map".to_string()));
-        assert!(result.contains("You take"));
+        game.player.location = GUARDIAN_CHAMBER.to_string();
+        game.player.take_item("ceremonial dagger");
 
-        // Try taking a nonexistent item
-        let result = game.process_command(Command::Take("gold coin".to_string()));
-        assert!(!game.player.inventory.contains(&"gold coin".to_string()));
-        assert!(result.contains("There is no"));
+        let result = game.process_command(Command::UseOn("dagger".to_string(), "statue".to_string())).message;
+        assert!(result.contains("the arms sag, lifeless"));
+        assert!(game.statue_disarmed);
+        assert!(game.player.has_item("ceremonial dagger"));
+
+        let result = game.process_command(Command::Take("golden idol".to_string())).message;
+        assert_eq!(result, "You take the golden idol.");
+    }
+
+    #[test]
+    fn test_take_all() {
+        let mut game = Game::new();
+        game.player.location = "Treasure Room".to_string();
+        let result = game.process_command(Command::TakeAll).message;
+        assert_eq!(result, "You take: gold coin, gold coin.");
+        assert!(game.get_room_items().is_empty());
+        assert_eq!(game.get_inventory_display(), "gold coin x2");
+
+        let result = game.process_command(Command::TakeAll).message;
+        assert_eq!(result, "There's nothing here to take.");
+    }
+
+    #[test]
+    fn test_take_all_respects_capacity() {
+        let mut game = Game::new();
+        game.player.max_weight = 2;
+        game.rooms.get_mut("Entrance Hall").unwrap().add_item("torch");
+        let result = game.process_command(Command::TakeAll).message;
+        assert_eq!(result, "You take: ancient map. You leave behind: torch (too heavy).");
+        assert_eq!(game.get_room_items(), vec!["torch".to_string()]);
+    }
+
+    #[test]
+    fn test_take_all_leaves_heavy_items_in_room_order() {
+        let mut game = Game::new();
+        game.player.max_weight = 4;
+        let room = game.rooms.get_mut("Entrance Hall").unwrap();
+        room.items.clear();
+        room.add_item("torch");
+        room.add_item("golden idol");
+        room.add_item("ceremonial dagger");
+
+        let result = game.process_command(Command::TakeAll).message;
+        assert_eq!(result, "You take: torch. You leave behind: golden idol (too heavy), ceremonial dagger (too heavy).");
+        assert_eq!(game.get_inventory(), vec!["torch".to_string()]);
+    }
+
+    #[test]
+    fn test_drop_all() {
+        let mut game = Game::new();
+        game.player.take_item("torch");
+        game.player.take_item("ceremonial dagger");
+
+        let result = game.process_command(Command::DropAll).message;
+        assert_eq!(result, "You drop: torch, ceremonial dagger.");
+        assert_eq!(game.player.display_inventory(), "Your inventory is empty.");
+        assert_eq!(game.get_room_items(), vec!["ancient map", "torch", "ceremonial dagger"]);
+
+        // Dropped items can be picked up again
+        assert_eq!(game.process_command(Command::Take("torch".to_string())).message, "You take the torch.");
+    }
+
+    #[test]
+    fn test_drop_all_with_empty_hands() {
+        let mut game = Game::new();
+        let result = game.process_command(Command::DropAll).message;
+        assert_eq!(result, "You aren't carrying anything.");
+    }
+
+    /// Records every event it is told about
+    #[derive(Default)]
+    struct RecordingSink {
+        events: RefCell<Vec<String>>,
+        /// Each command processed, with whether it moved the player and whether it won
+        commands: RefCell<Vec<(Command, bool, bool)>>,
+    }
+
+    impl EventSink for RecordingSink {
+        fn on_enter_room(&self, name: &str) {
+            self.events.borrow_mut().push(format!("enter {}", name));
+        }
+
+        fn on_take_item(&self, item: &str) {
+            self.events.borrow_mut().push(format!("take {}", item));
+        }
+
+        fn on_command(&self, command: &Command, result: &CommandResult) {
+            self.commands.borrow_mut().push((command.clone(), result.room_changed, result.won));
+        }
+    }
+
+    #[test]
+    fn test_event_sink_is_notified() {
+        let sink = Rc::new(RecordingSink::default());
+        let mut game = Game::new();
+        game.set_event_sink(sink.clone());
+
+        game.process_command(Command::Take("ancient map".to_string()));
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Go(Direction::South));
+        assert_eq!(*sink.events.borrow(), vec!["take ancient map", "enter Ceremonial Antechamber", "enter Entrance Hall"]);
+    }
+
+    #[test]
+    fn test_event_sink_sees_every_command() {
+        let sink = Rc::new(RecordingSink::default());
+        let mut game = Game::new_with_start("Treasure Room", &["golden idol"]).unwrap();
+        game.set_event_sink(sink.clone());
+
+        game.process_command(Command::Use("golden idol".to_string()));
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Use("golden idol".to_string()));
+
+        let commands = sink.commands.borrow();
+        assert_eq!(commands.len(), 3);
+        assert_eq!(commands[1], (Command::Go(Direction::North), true, false));
+        assert_eq!(commands[2], (Command::Use("golden idol".to_string()), false, true));
+    }
+
+    #[test]
+    fn test_journal_notes() {
+        let mut game = Game::new();
+        assert!(game.process_command(Command::Notes).message.contains("journal is empty"));
+
+        game.process_command(Command::Note("the map lies".to_string()));
+        game.process_command(Command::Note("try the crypt".to_string()));
+        let journal = game.process_command(Command::Notes).message;
+        assert_eq!(journal, "Your journal:\n1. the map lies\n2. try the crypt");
+
+        // Notes survive saving and loading
+        let mut storage = MemoryStorage::new();
+        game.save(&mut storage, "slot").unwrap();
+        let loaded = Game::load(&storage, "slot").unwrap();
+        assert_eq!(loaded.notes, game.notes);
+    }
+
+    #[test]
+    fn test_reading_inscription_records_discovery() {
+        let mut game = Game::new();
+        game.player.location = "Treasure Room".to_string();
+        game.process_command(Command::Read("inscription".to_string()));
+        game.process_command(Command::Read("inscription".to_string()));
+
+        let journal = game.process_command(Command::Notes).message;
+        assert_eq!(
+            journal,
+            "Your journal:\n1. inscription (Treasure Room): The inscription reads: 'Place the sacred idol to reveal the path.'"
+        );
+    }
+
+    #[test]
+    fn test_new_with_start() {
+        let mut game = Game::new_with_start("Treasure Room", &["golden idol"]).unwrap();
+        assert_eq!(game.player.location, "Treasure Room");
+        assert!(game.player.has_item("golden idol"));
+        assert!(!game.rooms["Guardian Chamber"].has_item("golden idol"));
+
+        game.process_command(Command::Use("golden idol".to_string()));
+        game.process_command(Command::Go(Direction::North));
+        let result = game.process_command(Command::Use("golden idol".to_string()));
+        assert!(result.won);
+
+        // Restarting puts the player back in the same spot with the same pack
+        game.process_command(Command::Restart);
+        assert_eq!(game.player.location, "Treasure Room");
+        assert!(game.player.has_item("golden idol"));
+        assert!(!game.rooms["Guardian Chamber"].has_item("golden idol"));
+        assert_eq!(game.path_history(), ["Treasure Room".to_string()]);
+
+        assert!(Game::new_with_start("Broom Cupboard", &[]).is_err());
+    }
+
+    #[test]
+    fn test_trap_damages_on_first_entry() {
+        let mut game = Game::new();
+        game.rooms.get_mut("Ceremonial Antechamber").unwrap().traps.clear();
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert!(result.ends_with("you lose 25 health (75 left)."));
+        assert_eq!(game.player.health, 75);
+
+        // Without a trap to disarm, it is spent once the room has been visited
+        game.process_command(Command::Go(Direction::South));
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert!(!result.contains("trap"));
+        assert_eq!(game.player.health, 75);
+        assert!(game.process_command(Command::Inventory).message.ends_with("Health: 75"));
+    }
+
+    #[test]
+    fn test_dart_trap_springs_until_disarmed() {
+        let mut game = Game::new();
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Go(Direction::South));
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert!(result.contains("A trap springs"));
+        assert_eq!(game.player.health, 50);
+
+        let result = game.process_command(Command::Disarm("dart trap".to_string())).message;
+        assert_eq!(result, "You need a ceremonial dagger to disarm the dart trap.");
+        game.process_command(Command::Take("ceremonial dagger".to_string()));
+        let result = game.process_command(Command::Disarm("Dart Trap".to_string())).message;
+        assert!(result.ends_with("The dart trap is disarmed."));
+        let result = game.process_command(Command::Disarm("dart trap".to_string())).message;
+        assert_eq!(result, "The dart trap is already disarmed.");
+
+        game.process_command(Command::Go(Direction::South));
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert!(!result.contains("trap"));
+        assert_eq!(game.player.health, 50);
+
+        let result = game.process_command(Command::Disarm("pit".to_string())).message;
+        assert_eq!(result, "There's no pit here to disarm.");
+    }
+
+    #[test]
+    fn test_trap_can_kill() {
+        let mut game = Game::new();
+        game.player.health = 25;
+        let result = game.process_command(Command::Go(Direction::North));
+        assert!(result.game_over);
+        assert!(result.message.ends_with("Game over."));
+        assert!(!game.player.is_alive());
+    }
+
+    #[test]
+    fn test_status_line() {
+        let mut game = Game::new();
+        game.process_command(Command::Take("ancient map".to_string()));
+        let status = game.process_command(Command::Status).message;
+        assert!(status.contains("Location: Entrance Hall"));
+        assert!(status.contains("Items: 1"));
+        assert!(status.contains("Health: 100"));
+    }
+
+    #[test]
+    fn test_fresh_game_is_winnable() {
+        let game = Game::new();
+        assert!(game.is_winnable());
+    }
+
+    #[test]
+    fn test_idol_left_in_sealed_room_is_unwinnable() {
+        let mut game = Game::new();
+        // A vault with a one-way exit and no way back in
+        let vault = Room::new("Sealed Vault", "Smooth walls surround you.", false, None)
+            .with_exit(Direction::East, "Entrance Hall");
+        game.rooms.insert(vault.name.clone(), vault);
+        game.rooms.get_mut(GUARDIAN_CHAMBER).unwrap().remove_item("golden idol");
+        game.player.location = "Sealed Vault".to_string();
+        game.player.take_item("golden idol");
+
+        game.process_command(Command::Drop("golden idol".to_string()));
+        let result = game.process_command(Command::Yes).message;
+        assert!(!result.contains("uneasy"));
+        assert!(game.is_winnable());
+
+        let result = game.process_command(Command::Go(Direction::East)).message;
+        assert!(result.contains("beyond your reach"));
+        assert!(!game.is_winnable());
+    }
+
+    #[test]
+    fn test_custom_messages_change_text_but_not_play() {
+        let messages = Messages {
+            no_item_here: "Nothing called {item} lies about.".to_string(),
+            ..Messages::default()
+        };
+        let mut game = Game::new().with_messages(messages);
+
+        let result = game.process_command(Command::Take("torch".to_string())).message;
+        assert_eq!(result, "Nothing called torch lies about.");
+        let result = game.process_command(Command::Take("ancient map".to_string())).message;
+        assert_eq!(result, "You take the ancient map.");
+        assert!(game.player.has_item("ancient map"));
+
+        // Restarting keeps the chosen text
+        game.process_command(Command::Restart);
+        let result = game.process_command(Command::Take("torch".to_string())).message;
+        assert_eq!(result, "Nothing called torch lies about.");
+    }
+
+    #[test]
+    fn test_dropping_idol_asks_first() {
+        let mut game = Game::new_with_start("Treasure Room", &["golden idol"]).unwrap();
+
+        let result = game.process_command(Command::Drop("golden idol".to_string())).message;
+        assert_eq!(result, "The golden idol seems important. Drop it anyway? (y/n)");
+        let result = game.process_command(Command::Look).message;
+        assert_eq!(result, "You keep hold of the golden idol.");
+        assert!(game.player.has_item("golden idol"));
+
+        game.process_command(Command::Drop("golden idol".to_string()));
+        let result = game.process_command(Command::Yes).message;
+        assert_eq!(result, "You drop the golden idol.");
+        assert!(!game.player.has_item("golden idol"));
+        assert!(game.get_room_items().contains(&"golden idol".to_string()));
+    }
+
+    #[test]
+    fn test_debug_lines_only_when_enabled() {
+        let mut game = Game::new().with_debug(true);
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert!(result.contains("[debug] moved Entrance Hall -> Ceremonial Antechamber via north"));
+
+        let mut game = Game::new();
+        let result = game.process_command(Command::Go(Direction::North)).message;
+        assert!(!result.contains("[debug]"));
+
+        assert!(debug_enabled(Some("1")));
+        assert!(!debug_enabled(Some("0")));
+        assert!(!debug_enabled(None));
+    }
+
+    #[test]
+    fn test_sorted_inventory_is_alphabetical() {
+        let mut game = Game::new();
+        for item in ["torch", "ancient map", "golden idol", "ceremonial dagger"] {
+            game.player.take_item(item);
+        }
+
+        let expected = ["ancient map", "ceremonial dagger", "golden idol", "torch"].map(String::from);
+        assert_eq!(game.get_sorted_inventory(), expected);
+        // Pickup order is kept for the plain listing
+        assert_eq!(game.get_inventory()[0], "torch");
+
+        let result = game.process_command(Command::SortedInventory).message;
+        assert!(result.contains("- ancient map\n- ceremonial dagger\n- golden idol\n- torch"));
+    }
+
+    #[test]
+    fn test_entrance_hall_exits() {
+        let game = Game::new();
+        let mut exits = game.get_room_exits();
+        exits.sort_by_key(|direction| direction.to_string());
+        assert_eq!(exits, vec![Direction::East, Direction::North]);
+    }
+
+    #[test]
+    fn test_footsteps_follow_the_route_taken() {
+        let mut game = Game::new();
+        for direction in [Direction::North, Direction::West, Direction::East, Direction::South, Direction::East] {
+            game.process_command(Command::Go(direction));
+        }
+
+        assert_eq!(game.path_history(), [
+            "Entrance Hall", "Ceremonial Antechamber", "Guardian Chamber", "Ceremonial Antechamber",
+            "Entrance Hall", "Ancient Crypt",
+        ].map(String::from));
+        assert_eq!(game.footsteps(), [
+            "Entrance Hall", "Ceremonial Antechamber", "Guardian Chamber", "Ancient Crypt",
+        ].map(String::from));
+
+        let result = game.process_command(Command::Retrace).message;
+        assert_eq!(result, "Your footsteps from the Entrance Hall: north, west, east, south, east");
+
+        let map = game.process_command(Command::Map).message;
+        let order: Vec<usize> = ["1. ", "2. ", "3. ", "4. "].iter().map(|step| map.find(step).unwrap()).collect();
+        assert!(order.is_sorted());
+        let third = map.lines().find(|line| line.starts_with("3. ")).unwrap();
+        assert!(third.contains("Guardian Chamber"));
+    }
+
+    #[test]
+    fn test_retrace_before_moving() {
+        let mut game = Game::new();
+        assert_eq!(game.process_command(Command::Retrace).message, "You haven't gone anywhere yet.");
+    }
+
+    #[test]
+    fn test_fixed_items_stay_put() {
+        let mut game = Game::new();
+        game.process_command(Command::Go(Direction::North));
+        game.process_command(Command::Go(Direction::West));
+        assert!(game.get_current_room_description().contains("statue"));
+
+        let result = game.process_command(Command::Take("statue".to_string())).message;
+        assert_eq!(result, "The statue is far too heavy and firmly fixed in place.");
+        assert!(!game.player.has_item("statue"));
+        assert!(game.get_current_room().unwrap().has_item("statue"));
+        assert_eq!(game.get_room_items(), vec!["golden idol".to_string()]);
+
+        let result = game.process_command(Command::Take("golden idol".to_string())).message;
+        assert!(result.starts_with("You take the golden idol."));
+        let result = game.process_command(Command::TakeAll).message;
+        assert_eq!(result, "There's nothing here to take.");
+    }
+
+    #[test]
+    fn test_cave_ins_never_break_winnability() {
+        let config = GameConfig { cave_in_after: 2, cave_in_every: 2, turn_limit: 100, ..GameConfig::default() };
+        for seed in 1..20 {
+            let mut game = Game::with_rng(config.clone(), Rng::new(seed));
+            game.rooms.get_mut("Ceremonial Antechamber").unwrap().trap_damage = None;
+            let mut rumbles = 0;
+            for step in 0..20 {
+                let direction = if step % 2 == 0 { Direction::North } else { Direction::South };
+                let result = game.process_command(Command::Go(direction)).message;
+                if result.contains("A distant rumble") {
+                    rumbles += 1;
+                }
+                assert!(game.is_winnable());
+            }
+
+            // Only the crypt can go, since the idol lies in the other dead end
+            assert_eq!(rumbles, 1);
+            assert!(!game.rooms["Entrance Hall"].exits.contains_key(&Direction::East));
+            assert!(game.rooms["Ceremonial Antechamber"].exits.contains_key(&Direction::West));
+            let result = game.process_command(Command::Go(Direction::East)).message;
+            assert!(result.contains("Fallen rubble"));
+        }
+    }
+
+    #[test]
+    fn test_cave_ins_wait_for_their_turn() {
+        let config = GameConfig { cave_in_after: 3, cave_in_every: 5, ..GameConfig::default() };
+        let mut game = Game::with_rng(config, Rng::new(7));
+        game.turns = 2;
+        assert!(!game.cave_in_due(0));
+        game.turns = 3;
+        assert!(game.cave_in_due(2));
+        game.turns = 7;
+        assert!(!game.cave_in_due(3));
+        // A walk over several rooms can pass a cave-in turn
+        game.turns = 9;
+        assert!(game.cave_in_due(7));
+    }
+
+    #[test]
+    fn test_give_coin_to_guardian() {
+        let mut game = Game::new_with_start(GUARDIAN_CHAMBER, &["gold coin", "torch"]).unwrap();
+
+        let result = game.process_command(Command::Give("torch".to_string(), "guardian".to_string())).message;
+        assert_eq!(result, "The guardian has no use for the torch.");
+        assert!(game.player.has_item("torch"));
+
+        let result = game.process_command(Command::Give("gold coin".to_string(), "Guardian".to_string())).message;
+        assert!(result.contains("the pedestal among the treasures waits for the idol"));
+        assert!(!game.player.has_item("gold coin"));
+    }
+
+    #[test]
+    fn test_give_requires_item_and_someone_to_take_it() {
+        let mut game = Game::new_with_start(GUARDIAN_CHAMBER, &["torch"]).unwrap();
+        let result = game.process_command(Command::Give("gold coin".to_string(), "guardian".to_string())).message;
+        assert_eq!(result, "You don't have a gold coin.");
+
+        let result = game.process_command(Command::Give("torch".to_string(), "priest".to_string())).message;
+        assert_eq!(result, "There's no one here called priest.");
+        assert!(game.player.has_item("torch"));
+    }
+
+    #[test]
+    fn test_revisits_and_dark_rooms_are_described_briefly() {
+        let mut game = Game::new();
+        game.process_command(Command::Go(Direction::North));
+        let result = game.process_command(Command::Go(Direction::South)).message;
+        assert!(result.starts_with("[ Entrance Hall ]\n\nExits:"));
+        assert!(game.look_around().contains("You stand in"));
+
+        let result = game.process_command(Command::Go(Direction::East)).message;
+        assert!(result.contains("too dark to make out"));
+        game.process_command(Command::Take("torch".to_string()));
+        game.process_command(Command::Use("torch".to_string()));
+        assert!(game.look_around().contains("Stone sarcophagi"));
+    }
+
+    #[test]
+    fn test_first_item_unlocks_achievement() {
+        let mut game = Game::new();
+        let listing = game.process_command(Command::Achievements).message;
+        assert_eq!(listing, "Achievements (0/4):\n- ???\n- ???\n- ???\n- ???");
+
+        game.process_command(Command::Take("ancient map".to_string()));
+        assert!(game.achievements.contains(&Achievement::FirstItem));
+
+        let listing = game.process_command(Command::Achievements).message;
+        assert_eq!(listing, "Achievements (1/4):\n- Finders Keepers: Pick up your first item.\n- ???\n- ???\n- ???");
+    }
+
+    #[test]
+    fn test_achievements_survive_save_and_load() {
+        let mut game = Game::new();
+        game.process_command(Command::Take("ancient map".to_string()));
+
+        let mut storage = MemoryStorage::new();
+        game.save(&mut storage, "slot").unwrap();
+        let loaded = Game::load(&storage, "slot").unwrap();
+        assert!(loaded.achievements.contains(&Achievement::FirstItem));
+    }
+
+    #[test]
+    fn test_examine_room_reveals_details() {
+        let mut game = Game::new_with_start("Treasure Room", &[]).unwrap();
+        let result = game.process_command(Command::Examine).message;
+        assert!(result.starts_with("Up close, the pedestal's top is worn into a shallow hollow"));
+        assert_eq!(game.turns, 1);
+
+        game.player.location = "Entrance Hall".to_string();
+        let result = game.process_command(Command::Examine).message;
+        assert_eq!(result, "You look closely, but notice nothing out of the ordinary.");
+    }
+
+    #[test]
+    fn test_torch_burns_out_after_its_uses() {
+        let mut game = Game::new_with_start("Ancient Crypt", &["torch", "ancient map"]).unwrap();
+        let result = game.process_command(Command::Use("torch".to_string())).message;
+        assert!(result.starts_with("You light the torch."));
+        assert_eq!(game.player.uses_remaining["torch"], 4);
+
+        // Items that aren't consumable last forever
+        game.process_command(Command::Use("ancient map".to_string()));
+        assert!(game.player.has_item("ancient map"));
+
+        for _ in 0..3 {
+            game.process_command(Command::Use("torch".to_string()));
+        }
+        assert_eq!(game.player.uses_remaining["torch"], 1);
+        let result = game.process_command(Command::Use("torch".to_string())).message;
+        assert!(result.ends_with("Your torch sputters and dies."));
+        assert!(!game.player.has_item("torch"));
+        assert!(!game.player.uses_remaining.contains_key("torch"));
+    }
+
+    #[test]
+    fn test_echo_repeats_the_last_word() {
+        assert_eq!(echo("hello in there"), "Your voice echoes: 'hello in there... there... ere...'");
+        assert_eq!(echo("input"), "Your voice echoes: 'input... input... put...'");
+    }
+
+    #[test]
+    fn test_shout_toggles_echoing_unknown_input() {
+        let mut game = Game::new();
+        let unknown = Command::Unknown("anyone here".to_string());
+        assert!(game.process_command(unknown.clone()).message.starts_with("I don't understand"));
+
+        game.process_command(Command::Shout);
+        assert_eq!(game.process_command(unknown.clone()).message, echo("anyone here"));
+
+        game.process_command(Command::Shout);
+        assert!(game.process_command(unknown).message.starts_with("I don't understand"));
+    }
+
+    #[test]
+    fn test_shouting_says_words_that_look_like_verbs() {
+        let mut game = Game::new();
+        assert!(game.parse_line("hello").is_err());
+
+        assert_eq!(game.run_script(&["shout", "hello"])[1], echo("hello"));
+        assert_eq!(game.parse_input("hello; north")[0], Ok(Command::Unknown("hello".to_string())));
+
+        // Shouting survives a restart like other settings
+        game.reset();
+        assert_eq!(game.run_script(&["hello"])[0], echo("hello"));
+    }
+
+    #[test]
+    fn test_command_result_lists_everything_taken_by_take_all() {
+        let mut game = Game::new();
+        game.player.location = "Treasure Room".to_string();
+        let result = game.process_command(Command::TakeAll);
+        assert_eq!(result.items_taken, vec!["gold coin".to_string(), "gold coin".to_string()]);
+        assert_eq!(result.item_taken, Some("gold coin".to_string()));
+
+        let result = game.process_command(Command::TakeAll);
+        assert!(result.items_taken.is_empty());
+        assert_eq!(result.item_taken, None);
+    }
+
+    #[test]
+    fn test_command_result_reports_item_taken_after_swap() {
+        let mut game = Game::new_with_start(GUARDIAN_CHAMBER, &["ceremonial dagger", "torch", "ancient map"]).unwrap();
+        game.player.max_weight = 9;
+
+        let result = game.process_command(Command::Take("golden idol".to_string()));
+        assert_eq!(result.item_taken, None);
+        let answer = game.parse_line("dagger").unwrap();
+        let result = game.process_command(answer);
+        assert_eq!(result.item_taken, Some("golden idol".to_string()));
+        assert_eq!(result.items_taken, vec!["golden idol".to_string()]);
+
+        // Turning the offer down takes nothing
+        game.process_command(Command::Drop("golden idol".to_string()));
+        game.process_command(Command::Take("ceremonial dagger".to_string()));
+        game.process_command(Command::Take("golden idol".to_string()));
+        let result = game.process_command(Command::No);
+        assert_eq!(result.item_taken, None);
     }
 }
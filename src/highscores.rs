@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use crate::storage::Storage;
+
+/// Key the high-score table is stored under
+pub const HIGHSCORES_KEY: &str = "highscores.json";
+
+/// Number of runs kept in the table
+pub const MAX_HIGH_SCORES: usize = 10;
+
+/// A completed run in the high-score table
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScoreEntry {
+    /// Name the player entered
+    pub name: String,
+    /// Final score of the run
+    pub score: i32,
+    /// Number of turns the run took
+    pub moves: u32,
+}
+
+/// Reads the high-score table, starting fresh if it is missing or unreadable
+pub fn load_scores(storage: &dyn Storage) -> Vec<ScoreEntry> {
+    storage.read(HIGHSCORES_KEY)
+        .and_then(|data| serde_json::from_slice(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Records a run in the stored high-score table
+pub fn save_score(storage: &mut dyn Storage, entry: ScoreEntry) -> Result<(), String> {
+    let mut scores = load_scores(storage);
+    insert_score(&mut scores, entry);
+    let json = serde_json::to_string_pretty(&scores)
+        .map_err(|e| format!("Failed to save high scores: {}", e))?;
+    storage.write(HIGHSCORES_KEY, json.as_bytes())
+}
+
+/// Adds a run to the table, highest score first, keeping only the best runs
+pub fn insert_score(scores: &mut Vec<ScoreEntry>, entry: ScoreEntry) {
+    // Earlier runs keep their place over later runs with the same score
+    let index = scores.iter().position(|existing| existing.score < entry.score).unwrap_or(scores.len());
+    scores.insert(index, entry);
+    scores.truncate(MAX_HIGH_SCORES);
+}
+
+/// Formats the high-score table for display
+pub fn format_scores(scores: &[ScoreEntry]) -> String {
+    if scores.is_empty() {
+        return "No high scores yet. Escape the temple to set one!".to_string();
+    }
+
+    let mut table = "High scores:".to_string();
+    for (rank, entry) in scores.iter().enumerate() {
+        table.push_str(&format!("\n{:>2}. {} - {} points in {} moves", rank + 1, entry.name, entry.score, entry.moves));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::storage::MemoryStorage;
+
+    fn entry(name: &str, score: i32) -> ScoreEntry {
+        ScoreEntry { name: name.to_string(), score, moves: 10 }
+    }
+
+    #[test]
+    fn test_insert_orders_by_score() {
+        let mut scores = Vec::new();
+        insert_score(&mut scores, entry("a", 50));
+        insert_score(&mut scores, entry("b", 120));
+        insert_score(&mut scores, entry("c", 50));
+        insert_score(&mut scores, entry("d", 80));
+
+        let names: Vec<&str> = scores.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["b", "d", "a", "c"]);
+    }
+
+    #[test]
+    fn test_insert_keeps_top_ten() {
+        let mut scores = Vec::new();
+        for score in 0..15 {
+            insert_score(&mut scores, entry("run", score));
+        }
+        assert_eq!(scores.len(), MAX_HIGH_SCORES);
+        assert_eq!(scores[0].score, 14);
+        assert_eq!(scores[MAX_HIGH_SCORES - 1].score, 5);
+
+        // Too low to make the table
+        insert_score(&mut scores, entry("late", 1));
+        assert!(scores.iter().all(|e| e.name != "late"));
+    }
+
+    #[test]
+    fn test_missing_or_corrupt_table_starts_fresh() {
+        let mut storage = MemoryStorage::new();
+        assert!(load_scores(&storage).is_empty());
+        storage.write(HIGHSCORES_KEY, b"not json").unwrap();
+        assert!(load_scores(&storage).is_empty());
+
+        save_score(&mut storage, entry("explorer", 90)).unwrap();
+        assert_eq!(load_scores(&storage), vec![entry("explorer", 90)]);
+    }
+}
@@ -1,100 +1,755 @@
 use crate::room::Direction;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::LazyLock;
 use std::io::{self, Write};
 
 /// Represents the possible commands a player can issue
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Command {
     /// Move in a direction (e.g., "go north")
     Go(Direction),
+    /// Return to the previous room (e.g., "back")
+    Back,
+    /// Move into a neighbouring room by name (e.g., "enter crypt")
+    GoNamed(String),
+    /// Walk to a visited room by the shortest known route (e.g., "goto temple exit")
+    GoTo(String),
     /// Pick up an item (e.g., "take key")
     Take(String),
+    /// Pick up everything in the room (e.g., "take all")
+    TakeAll,
+    /// Use an item on something (e.g., "use torch on tapestry")
+    UseOn(String, String),
+    /// Combine two carried items into a new one (e.g., "combine amulet with scarab")
+    Combine(String, String),
+    /// Drop an item (e.g., "drop key")
+    Drop(String),
+    /// Drop everything being carried (e.g., "drop all")
+    DropAll,
     /// Use an item (e.g., "use key")
     Use(String),
+    /// Throw a carried item, optionally at something (e.g., "throw dagger at statue")
+    Throw(String, Option<String>),
+    /// Open a container (e.g., "open sarcophagus")
+    Open(String),
+    /// Close a container (e.g., "close sarcophagus")
+    Close(String),
+    /// Search the room for hidden items (e.g., "search")
+    Search,
+    /// Disarm a trap in the room (e.g., "disarm dart trap")
+    Disarm(String),
     /// Display inventory (e.g., "inventory")
     Inventory,
+    /// Check the inventory in alphabetical order (e.g., "inventory sorted")
+    SortedInventory,
+    /// Summarize where the player is and how they are doing (e.g., "status")
+    Status,
     /// Look around the current room (e.g., "look")
     Look,
+    /// Peek through an exit without moving (e.g., "look north")
+    LookDirection(Direction),
+    /// Study the current room for subtle details (e.g., "examine room", "look closely")
+    Examine,
+    /// Talk to a character in the room (e.g., "talk to guardian")
+    Talk(String),
+    /// Hand a carried item to a character in the room (e.g., "give coin to guardian")
+    Give(String, String),
+    /// Read something written on an item (e.g., "read ancient map")
+    Read(String),
+    /// List where each exit leads (e.g., "exits")
+    Exits,
+    /// Show a map of visited rooms (e.g., "map")
+    Map,
+    /// Show the fastest way out once the game is won (e.g., "map solution")
+    SolutionMap,
+    /// List the directions taken so far (e.g., "retrace")
+    Retrace,
+    /// Attach a note to the current room (e.g., "label idol room")
+    Label(String),
+    /// Show the high-score table (e.g., "scores")
+    Scores,
+    /// List the milestones reached so far (e.g., "achievements")
+    Achievements,
+    /// Switch shouting unrecognised input into the temple on or off (e.g., "shout")
+    Shout,
+    /// Write an entry in the journal (e.g., "note the stars matter")
+    Note(String),
+    /// List the journal entries (e.g., "notes")
+    Notes,
+    /// Define a shorthand for a longer command (e.g., "alias gg = go north")
+    DefineAlias(String, String),
+    /// List the shorthands the player has defined (e.g., "aliases")
+    Aliases,
+    /// Ask for a suggestion of what to do next (e.g., "hint")
+    Hint,
+    /// Start the game over (e.g., "restart")
+    Restart,
+    /// Take back the last action that changed the game (e.g., "undo")
+    Undo,
+    /// Show the story that sets up the game again (e.g., "intro")
+    Intro,
     /// Help command to show available commands (e.g., "help")
     Help,
-    /// Quit the game (e.g., "quit")
+    /// Quit the game, once confirmed (e.g., "quit")
     Quit,
+    /// Confirm a question such as whether to quit (e.g., "yes")
+    Yes,
+    /// Turn down a question such as whether to quit (e.g., "no")
+    No,
     /// Unknown command
     Unknown(String),
 }
 
-/// Reads a line of input from the user
-pub fn read_input() -> String {
+/// Why a line of input couldn't be turned into a command
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// Nothing was typed
+    Empty,
+    /// The command is missing something it needs, holding the prompt asking for it
+    MissingArgument(&'static str),
+    /// A word given as a direction isn't one
+    UnknownDirection(String),
+    /// The command word looks like a typo of a known verb
+    UnknownCommand(String),
+    /// An alias kept expanding into other aliases, holding the alias typed
+    AliasLoop(String),
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "Please enter a command."),
+            ParseError::MissingArgument(prompt) => write!(f, "{}", prompt),
+            ParseError::UnknownDirection(word) => match closest_direction(word) {
+                Some(direction) => write!(f, "'{}' is not a valid direction. Did you mean '{}'?", word, direction),
+                None => write!(
+                    f,
+                    "'{}' is not a valid direction. Try 'north', 'east', 'south', or 'west'.",
+                    word
+                ),
+            },
+            ParseError::UnknownCommand(word) => match closest_verb(word) {
+                Some(verb) => write!(f, "Unknown command '{}'. Did you mean '{}'?", word, verb),
+                None => write!(f, "Unknown command '{}'.", word),
+            },
+            ParseError::AliasLoop(alias) => write!(f, "The alias '{}' keeps leading back to itself.", alias),
+        }
+    }
+}
+
+/// The kinds of command a verb can start, before its arguments are read
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandKind {
+    Go,
+    GoTo,
+    Back,
+    Enter,
+    Take,
+    Drop,
+    Use,
+    Throw,
+    Combine,
+    Open,
+    Close,
+    Search,
+    Disarm,
+    Inventory,
+    SortInventory,
+    Look,
+    Examine,
+    Talk,
+    Give,
+    Read,
+    Exits,
+    Map,
+    Retrace,
+    Label,
+    Note,
+    Status,
+    Alias,
+    Aliases,
+    Notes,
+    Restart,
+    Undo,
+    Hint,
+    Scores,
+    Achievements,
+    Shout,
+    Intro,
+    Help,
+    Quit,
+    Yes,
+    No,
+}
+
+/// Every verb the parser understands, including short forms and synonyms, with the kind of command it starts
+const VERBS: &[(&str, CommandKind)] = &[
+    ("go", CommandKind::Go), ("move", CommandKind::Go),
+    ("goto", CommandKind::GoTo),
+    ("back", CommandKind::Back), ("b", CommandKind::Back),
+    ("enter", CommandKind::Enter),
+    ("take", CommandKind::Take), ("get", CommandKind::Take), ("pickup", CommandKind::Take),
+    ("drop", CommandKind::Drop), ("discard", CommandKind::Drop),
+    ("use", CommandKind::Use), ("place", CommandKind::Use),
+    ("throw", CommandKind::Throw),
+    ("combine", CommandKind::Combine),
+    ("open", CommandKind::Open),
+    ("close", CommandKind::Close),
+    ("search", CommandKind::Search),
+    ("disarm", CommandKind::Disarm),
+    ("inventory", CommandKind::Inventory), ("i", CommandKind::Inventory), ("inv", CommandKind::Inventory),
+    ("sort", CommandKind::SortInventory),
+    ("look", CommandKind::Look), ("l", CommandKind::Look),
+    ("examine", CommandKind::Examine), ("x", CommandKind::Examine),
+    ("talk", CommandKind::Talk),
+    ("give", CommandKind::Give), ("offer", CommandKind::Give),
+    ("read", CommandKind::Read),
+    ("exits", CommandKind::Exits), ("where", CommandKind::Exits),
+    ("map", CommandKind::Map), ("m", CommandKind::Map),
+    ("retrace", CommandKind::Retrace), ("footsteps", CommandKind::Retrace),
+    ("label", CommandKind::Label), ("annotate", CommandKind::Label),
+    ("note", CommandKind::Note),
+    ("status", CommandKind::Status), ("stat", CommandKind::Status),
+    ("alias", CommandKind::Alias),
+    ("aliases", CommandKind::Aliases),
+    ("notes", CommandKind::Notes), ("journal", CommandKind::Notes),
+    ("restart", CommandKind::Restart), ("new", CommandKind::Restart),
+    ("undo", CommandKind::Undo),
+    ("hint", CommandKind::Hint),
+    ("scores", CommandKind::Scores), ("highscores", CommandKind::Scores),
+    ("achievements", CommandKind::Achievements), ("progress", CommandKind::Achievements),
+    ("shout", CommandKind::Shout),
+    ("intro", CommandKind::Intro), ("story", CommandKind::Intro),
+    ("help", CommandKind::Help), ("h", CommandKind::Help),
+    ("quit", CommandKind::Quit), ("exit", CommandKind::Quit), ("q", CommandKind::Quit),
+    ("yes", CommandKind::Yes), ("y", CommandKind::Yes),
+    ("no", CommandKind::No),
+];
+
+/// The verbs understood when no other table is given
+static DEFAULT_VERBS: LazyLock<VerbTable> = LazyLock::new(VerbTable::default);
+
+/// Looks up which kind of command a verb starts, so synonyms can be added without touching the parser
+#[derive(Debug, Clone, PartialEq)]
+pub struct VerbTable {
+    verbs: HashMap<String, CommandKind>,
+    /// Whether a misspelled direction after 'go' is quietly corrected instead of suggested
+    correct_directions: bool,
+}
+
+impl Default for VerbTable {
+    fn default() -> Self {
+        Self {
+            verbs: VERBS.iter().map(|(verb, kind)| (verb.to_string(), *kind)).collect(),
+            correct_directions: false,
+        }
+    }
+}
+
+impl VerbTable {
+    /// Adds a synonym for a kind of command, replacing anything the word meant before
+    pub fn register(&mut self, verb: &str, kind: CommandKind) {
+        self.verbs.insert(verb.to_lowercase(), kind);
+    }
+
+    /// Chooses whether 'go norht' moves north or asks if north was meant
+    pub fn set_correct_directions(&mut self, correct: bool) {
+        self.correct_directions = correct;
+    }
+
+    /// Gets the kind of command a verb starts
+    pub fn lookup(&self, verb: &str) -> Option<CommandKind> {
+        self.verbs.get(verb).copied()
+    }
+}
+
+/// Largest edit distance at which an unknown verb is still treated as a typo
+const MAX_SUGGESTION_DISTANCE: usize = 2;
+
+/// Most aliases that can be expanded in a row before giving up on a loop
+const MAX_ALIAS_DEPTH: usize = 8;
+
+/// Reads a line of input from the user, or None once stdin is closed or can't be read
+pub fn read_input() -> Option<String> {
     print!("> ");
-    // Flush to ensure the prompt is displayed before reading input
-    io::stdout().flush().expect("Failed to flush stdout");
+    // Flush to ensure the prompt is displayed before reading input; a prompt that
+    // fails to show is no reason to stop reading
+    let _ = io::stdout().flush();
 
     let mut input = String::new();
-    io::stdin()
-        .read_line(&mut input)
-        .expect("Failed to read input");
+    match io::stdin().read_line(&mut input) {
+        Ok(0) | Err(_) => None,
+        Ok(_) => Some(input.trim().to_string()),
+    }
+}
 
-    input.trim().to_string()
+/// A source of command lines, such as the terminal or a recorded script
+pub trait InputSource {
+    /// Gets the next line of input, or None once the input has run out
+    fn next_command_line(&mut self) -> Option<String>;
+}
+
+/// Reads command lines typed at the terminal
+pub struct StdinInput;
+
+impl InputSource for StdinInput {
+    fn next_command_line(&mut self) -> Option<String> {
+        read_input()
+    }
+}
+
+/// Replays recorded command lines, oldest first
+impl InputSource for Vec<String> {
+    fn next_command_line(&mut self) -> Option<String> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+}
+
+/// The command-line flag naming a file of commands to run on startup
+pub const SCRIPT_FLAG: &str = "--script";
+/// The command-line flag that quits once the startup script has run
+pub const EXIT_AFTER_SCRIPT_FLAG: &str = "--exit-after-script";
+
+/// Finds the script file given after `--script` on the command line, if any
+pub fn script_path(args: &[String]) -> Option<&str> {
+    let flag = args.iter().position(|arg| arg == SCRIPT_FLAG)?;
+    args.get(flag + 1).map(|path| path.as_str())
+}
+
+/// Replays the command lines of a script file, skipping blank lines and `#` comments
+pub struct ScriptInput {
+    lines: Vec<String>,
+}
+
+impl ScriptInput {
+    /// Creates a script from its text
+    pub fn new(text: &str) -> Self {
+        let lines = text
+            .lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(|line| line.to_string())
+            .collect();
+        ScriptInput { lines }
+    }
+
+    /// Reads a script from a file
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        std::fs::read_to_string(path)
+            .map(|text| Self::new(&text))
+            .map_err(|error| format!("Couldn't read script '{}': {}", path, error))
+    }
+}
+
+impl InputSource for ScriptInput {
+    fn next_command_line(&mut self) -> Option<String> {
+        self.lines.next_command_line()
+    }
+}
+
+/// Parses input that may chain several commands with ';' or "then"
+pub fn parse_commands(input: &str) -> Vec<Result<Command, ParseError>> {
+    parse_commands_with_aliases(input, &HashMap::new(), &DEFAULT_VERBS)
+}
+
+/// Replaces an alias at the start of the input with the command it stands for, following
+/// aliases of aliases up to a limit
+pub fn expand_alias(input: &str, aliases: &HashMap<String, String>) -> Result<String, ParseError> {
+    let mut line = input.trim().to_lowercase();
+    for _ in 0..MAX_ALIAS_DEPTH {
+        let (first, rest) = line.split_once(' ').unwrap_or((line.as_str(), ""));
+        let Some(target) = aliases.get(first) else {
+            return Ok(line);
+        };
+        line = format!("{} {}", target, rest).trim().to_string();
+    }
+
+    let alias = input.split_whitespace().next().unwrap_or_default().to_lowercase();
+    Err(ParseError::AliasLoop(alias))
+}
+
+/// Parses chained input like `parse_commands`, first expanding the player's aliases in each command
+/// and understanding the verbs in the given table
+pub fn parse_commands_with_aliases(
+    input: &str,
+    aliases: &HashMap<String, String>,
+    verbs: &VerbTable,
+) -> Vec<Result<Command, ParseError>> {
+    let segments = split_commands(input);
+
+    // Without any commands, fall back to the single-command error
+    if segments.is_empty() {
+        return vec![parse_command_with(input, verbs)];
+    }
+
+    segments.iter()
+        .map(|segment| expand_alias(segment, aliases).and_then(|line| parse_command_with(&line, verbs)))
+        .collect()
+}
+
+/// Splits input into the commands chained with ';' or "then", leaving out empty ones
+pub fn split_commands(input: &str) -> Vec<String> {
+    let mut segments = Vec::new();
+    for part in input.split(';') {
+        let mut segment: Vec<&str> = Vec::new();
+        for word in part.split_whitespace() {
+            if word.eq_ignore_ascii_case("then") {
+                segments.push(segment.join(" "));
+                segment.clear();
+            } else {
+                segment.push(word);
+            }
+        }
+        segments.push(segment.join(" "));
+    }
+    segments.retain(|segment| !segment.is_empty());
+    segments
 }
 
 /// Parses user input into a Command enum
-pub fn parse_command(input: &str) -> Result<Command, String> {
+pub fn parse_command(input: &str) -> Result<Command, ParseError> {
+    parse_command_with(input, &DEFAULT_VERBS)
+}
+
+/// Parses user input into a Command enum, understanding the verbs in the given table
+pub fn parse_command_with(input: &str, verbs: &VerbTable) -> Result<Command, ParseError> {
     let input = input.trim().to_lowercase();
 
     if input.is_empty() {
-        return Err("Please enter a command.".to_string());
+        return Err(ParseError::Empty);
     }
 
     // Split the input into words
     let mut words: Vec<&str> = input.split_whitespace().collect();
 
     if words.is_empty() {
-        return Err("Please enter a command.".to_string());
+        return Err(ParseError::Empty);
     }
 
     let command = words[0];
     words.remove(0); // Remove the command, leaving only arguments
 
-    match command {
-        "go" | "move" => {
+    // A bare direction such as "n" or "north" moves that way
+    if words.is_empty()
+        && let Ok(direction) = command.parse::<Direction>()
+    {
+        return Ok(Command::Go(direction));
+    }
+
+    let Some(kind) = verbs.lookup(command) else {
+        return match closest_verb(command) {
+            Some(_) => Err(ParseError::UnknownCommand(command.to_string())),
+            None => Ok(Command::Unknown(input)),
+        };
+    };
+
+    match kind {
+        CommandKind::Go => {
+            if words.is_empty() {
+                return Err(ParseError::MissingArgument("Go where? Try 'go north', 'go east', 'go south', or 'go west'."));
+            }
+
+            if words == ["back"] {
+                return Ok(Command::Back);
+            }
+
+            // "go to [room]"
+            if words[0] == "to" {
+                if words.len() == 1 {
+                    return Err(ParseError::MissingArgument("Go to where? Please name a room."));
+                }
+                return Ok(Command::GoNamed(words[1..].join(" ")));
+            }
+
+            match words[0].parse::<Direction>() {
+                Ok(direction) => Ok(Command::Go(direction)),
+                Err(_) => match closest_direction(words[0]) {
+                    Some(direction) if verbs.correct_directions => Ok(Command::Go(direction)),
+                    _ => Err(ParseError::UnknownDirection(words[0].to_string())),
+                },
+            }
+        },
+        CommandKind::GoTo => {
             if words.is_empty() {
-                return Err("Go where? Try 'go north', 'go east', 'go south', or 'go west'.".to_string());
+                return Err(ParseError::MissingArgument("Go to where? Please name a room you have visited."));
             }
 
-            match Direction::from_string(words[0]) {
-                Some(direction) => Ok(Command::Go(direction)),
-                None => Err(format!("'{}' is not a valid direction. Try 'north', 'east', 'south', or 'west'.", words[0])),
+            Ok(Command::GoTo(words.join(" ")))
+        },
+        CommandKind::Back => {
+            Ok(Command::Back)
+        },
+        CommandKind::Enter => {
+            if words.is_empty() {
+                return Err(ParseError::MissingArgument("Enter where? Please name a room."));
             }
+
+            Ok(Command::GoNamed(words.join(" ")))
         },
-        "take" | "get" | "pickup" => {
+        CommandKind::Take => {
             if words.is_empty() {
-                return Err("Take what? Please specify an item.".to_string());
+                return Err(ParseError::MissingArgument("Take what? Please specify an item."));
+            }
+            if words == ["all"] {
+                return Ok(Command::TakeAll);
             }
 
             Ok(Command::Take(words.join(" ")))
         },
-        "use" => {
+        CommandKind::Drop => {
+            if words.is_empty() {
+                return Err(ParseError::MissingArgument("Drop what? Please specify an item."));
+            }
+            if words == ["all"] {
+                return Ok(Command::DropAll);
+            }
+
+            Ok(Command::Drop(words.join(" ")))
+        },
+        CommandKind::Use => {
             if words.is_empty() {
-                return Err("Use what? Please specify an item.".to_string());
+                return Err(ParseError::MissingArgument("Use what? Please specify an item."));
+            }
+
+            // "use [item] on [target]"
+            if let Some(index) = words.iter().position(|w| *w == "on") {
+                if index == 0 || index == words.len() - 1 {
+                    return Err(ParseError::MissingArgument("Use what on what? Try 'use [item] on [target]'."));
+                }
+                return Ok(Command::UseOn(words[..index].join(" "), words[index + 1..].join(" ")));
             }
 
             Ok(Command::Use(words.join(" ")))
         },
-        "inventory" | "i" | "inv" => {
-            Ok(Command::Inventory)
+        CommandKind::Throw => {
+            // "throw [item] at [target]", where the target is whatever the item is aimed at
+            let (item, target) = match words.iter().position(|w| *w == "at") {
+                Some(index) if index > 0 && index < words.len() - 1 => {
+                    (&words[..index], Some(words[index + 1..].join(" ")))
+                },
+                Some(_) => return Err(ParseError::MissingArgument("Throw what at what? Try 'throw [item] at [target]'.")),
+                None => (&words[..], None),
+            };
+            if item.is_empty() {
+                return Err(ParseError::MissingArgument("Throw what? Please specify an item."));
+            }
+
+            Ok(Command::Throw(item.join(" "), target))
+        },
+        CommandKind::Combine => {
+            // "combine [item] with [item]"
+            match words.iter().position(|w| *w == "with") {
+                Some(index) if index > 0 && index < words.len() - 1 => {
+                    Ok(Command::Combine(words[..index].join(" "), words[index + 1..].join(" ")))
+                }
+                _ => Err(ParseError::MissingArgument("Combine what with what? Try 'combine [item] with [item]'.")),
+            }
+        },
+        CommandKind::Open => {
+            if words.is_empty() {
+                return Err(ParseError::MissingArgument("Open what? Please specify something to open."));
+            }
+
+            Ok(Command::Open(words.join(" ")))
+        },
+        CommandKind::Close => {
+            if words.is_empty() {
+                return Err(ParseError::MissingArgument("Close what? Please specify something to close."));
+            }
+
+            Ok(Command::Close(words.join(" ")))
+        },
+        CommandKind::Search => {
+            Ok(Command::Search)
+        },
+        CommandKind::Disarm => {
+            if words.is_empty() {
+                return Err(ParseError::MissingArgument("Disarm what? Please name a trap."));
+            }
+
+            Ok(Command::Disarm(words.join(" ")))
+        },
+        CommandKind::Inventory => {
+            match words.as_slice() {
+                [] => Ok(Command::Inventory),
+                ["sorted"] => Ok(Command::SortedInventory),
+                _ => Err(ParseError::MissingArgument("Try 'inventory' or 'inventory sorted'.")),
+            }
+        },
+        CommandKind::SortInventory => {
+            Ok(Command::SortedInventory)
+        },
+        CommandKind::Look => {
+            match words.as_slice() {
+                [] => Ok(Command::Look),
+                ["closely"] => Ok(Command::Examine),
+                [direction] => match direction.parse::<Direction>() {
+                    Ok(direction) => Ok(Command::LookDirection(direction)),
+                    Err(_) => Err(ParseError::UnknownDirection(direction.to_string())),
+                },
+                _ => Err(ParseError::MissingArgument("Try 'look' or 'look [direction]'.")),
+            }
+        },
+        CommandKind::Examine => {
+            match words.as_slice() {
+                [] | ["room"] => Ok(Command::Examine),
+                _ => Err(ParseError::MissingArgument("Try 'examine room'.")),
+            }
+        },
+        CommandKind::Talk => {
+            // "talk to [name]" or "talk [name]"
+            if words.first() == Some(&"to") {
+                words.remove(0);
+            }
+            if words.is_empty() {
+                return Err(ParseError::MissingArgument("Talk to whom? Please name someone."));
+            }
+
+            Ok(Command::Talk(words.join(" ")))
+        },
+        CommandKind::Give => {
+            // "give [item] to [name]"
+            match words.iter().position(|w| *w == "to") {
+                Some(index) if index > 0 && index < words.len() - 1 => {
+                    Ok(Command::Give(words[..index].join(" "), words[index + 1..].join(" ")))
+                }
+                _ => Err(ParseError::MissingArgument("Give what to whom? Try 'give [item] to [name]'.")),
+            }
+        },
+        CommandKind::Read => {
+            if words.is_empty() {
+                return Err(ParseError::MissingArgument("Read what? Please specify an item."));
+            }
+
+            Ok(Command::Read(words.join(" ")))
+        },
+        CommandKind::Exits => {
+            Ok(Command::Exits)
+        },
+        CommandKind::Map => {
+            match words.as_slice() {
+                [] => Ok(Command::Map),
+                ["solution"] => Ok(Command::SolutionMap),
+                _ => Err(ParseError::MissingArgument("Try 'map' or 'map solution'.")),
+            }
+        },
+        CommandKind::Retrace => {
+            Ok(Command::Retrace)
+        },
+        CommandKind::Label => {
+            if words.is_empty() {
+                return Err(ParseError::MissingArgument("Label it what? Please give the room a note."));
+            }
+
+            Ok(Command::Label(words.join(" ")))
+        },
+        CommandKind::Note => {
+            if words.is_empty() {
+                return Err(ParseError::MissingArgument("Note what? Please write something down."));
+            }
+
+            Ok(Command::Note(words.join(" ")))
+        },
+        CommandKind::Status => {
+            Ok(Command::Status)
         },
-        "look" | "l" => {
-            Ok(Command::Look)
+        CommandKind::Alias => {
+            // Split the definition on '=' into the shorthand and what it stands for
+            let definition = words.join(" ");
+            match definition.split_once('=') {
+                Some((name, target)) if name.split_whitespace().count() == 1 && !target.trim().is_empty() => {
+                    Ok(Command::DefineAlias(name.trim().to_string(), target.trim().to_string()))
+                },
+                _ => Err(ParseError::MissingArgument("Alias what? Try 'alias gg = go north'.")),
+            }
+        },
+        CommandKind::Aliases => {
+            Ok(Command::Aliases)
+        },
+        CommandKind::Notes => {
+            Ok(Command::Notes)
+        },
+        CommandKind::Restart => {
+            Ok(Command::Restart)
+        },
+        CommandKind::Undo => {
+            Ok(Command::Undo)
+        },
+        CommandKind::Hint => {
+            Ok(Command::Hint)
+        },
+        CommandKind::Scores => {
+            Ok(Command::Scores)
+        },
+        CommandKind::Achievements => {
+            Ok(Command::Achievements)
+        },
+        CommandKind::Shout => {
+            Ok(Command::Shout)
         },
-        "help" | "h" => {
+        CommandKind::Intro => {
+            Ok(Command::Intro)
+        },
+        CommandKind::Help => {
             Ok(Command::Help)
         },
-        "quit" | "exit" | "q" => {
+        CommandKind::Quit => {
             Ok(Command::Quit)
         },
-        _ => {
-            Ok(Command::Unknown(input))
+        CommandKind::Yes => {
+            Ok(Command::Yes)
+        },
+        CommandKind::No => {
+            Ok(Command::No)
+        },
+    }
+}
+
+/// Finds the known verb closest to a misspelled word, if it's close enough to be a typo
+fn closest_verb(word: &str) -> Option<&'static str> {
+    // Single letters are too short to be worth suggesting
+    VERBS.iter()
+        .map(|(verb, _)| *verb)
+        .chain(Direction::all().iter().map(Direction::as_str))
+        .filter(|verb| verb.len() > 1)
+        .map(|verb| (verb, levenshtein(word, verb)))
+        .filter(|&(_, distance)| distance <= MAX_SUGGESTION_DISTANCE && distance < word.chars().count())
+        .min_by_key(|&(_, distance)| distance)
+        .map(|(verb, _)| verb)
+}
+
+/// Finds the direction closest to a misspelled word, if it's close enough to be a typo
+fn closest_direction(word: &str) -> Option<Direction> {
+    Direction::all().into_iter()
+        .map(|direction| {
+            let distance = levenshtein(word, direction.as_str());
+            (direction, distance)
+        })
+        .filter(|&(_, distance)| distance <= MAX_SUGGESTION_DISTANCE && distance < word.chars().count())
+        .min_by_key(|&(_, distance)| distance)
+        .map(|(direction, _)| direction)
+}
+
+/// Computes the number of single-character edits needed to turn one word into another
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            current.push((previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1));
         }
+        previous = current;
     }
+
+    previous[b.len()]
 }
 
 /// Unit tests for the input module
@@ -112,11 +767,34 @@ mod tests {
         // Case insensitivity
         assert_eq!(parse_command("Go North"), Ok(Command::Go(Direction::North)));
 
+        // Abbreviated directions
+        assert_eq!(parse_command("go n"), Ok(Command::Go(Direction::North)));
+
         // Invalid direction
-        assert!(parse_command("go nowhere").is_err());
+        assert_eq!(parse_command("go nowhere"), Err(ParseError::UnknownDirection("nowhere".to_string())));
+        assert_eq!(
+            parse_command("go nowhere").unwrap_err().to_string(),
+            "'nowhere' is not a valid direction. Try 'north', 'east', 'south', or 'west'."
+        );
 
         // Missing direction
-        assert!(parse_command("go").is_err());
+        assert!(matches!(parse_command("go"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_misspelled_direction_is_suggested_or_corrected() {
+        let error = parse_command("go norht").unwrap_err();
+        assert_eq!(error, ParseError::UnknownDirection("norht".to_string()));
+        assert_eq!(error.to_string(), "'norht' is not a valid direction. Did you mean 'north'?");
+
+        let mut verbs = VerbTable::default();
+        verbs.set_correct_directions(true);
+        assert_eq!(parse_command_with("go norht", &verbs), Ok(Command::Go(Direction::North)));
+        assert_eq!(parse_command_with("go wset", &verbs), Ok(Command::Go(Direction::West)));
+        assert_eq!(
+            parse_command_with("go sideways", &verbs),
+            Err(ParseError::UnknownDirection("sideways".to_string()))
+        );
     }
 
     #[test]
@@ -126,16 +804,47 @@ mod tests {
         assert_eq!(parse_command("take golden idol"), Ok(Command::Take("golden idol".to_string())));
 
         // Missing item
-        assert!(parse_command("take").is_err());
+        assert!(matches!(parse_command("take"), Err(ParseError::MissingArgument(_))));
+        assert_eq!(parse_command("take all"), Ok(Command::TakeAll));
+        assert_eq!(parse_command("get all"), Ok(Command::TakeAll));
+    }
+
+    #[test]
+    fn test_parse_drop_command() {
+        assert_eq!(parse_command("drop torch"), Ok(Command::Drop("torch".to_string())));
+        assert_eq!(parse_command("discard golden idol"), Ok(Command::Drop("golden idol".to_string())));
+
+        // Missing item
+        assert!(matches!(parse_command("drop"), Err(ParseError::MissingArgument(_))));
+        assert_eq!(parse_command("drop all"), Ok(Command::DropAll));
     }
 
     #[test]
     fn test_parse_use_command() {
         assert_eq!(parse_command("use key"), Ok(Command::Use("key".to_string())));
         assert_eq!(parse_command("use golden idol"), Ok(Command::Use("golden idol".to_string())));
+        assert_eq!(
+            parse_command("use torch on star tapestry"),
+            Ok(Command::UseOn("torch".to_string(), "star tapestry".to_string()))
+        );
+        assert_eq!(
+            parse_command("use dagger on statue"),
+            Ok(Command::UseOn("dagger".to_string(), "statue".to_string()))
+        );
 
         // Missing item
-        assert!(parse_command("use").is_err());
+        assert!(matches!(parse_command("use"), Err(ParseError::MissingArgument(_))));
+
+        // Missing target or item around "on"
+        assert!(matches!(parse_command("use torch on"), Err(ParseError::MissingArgument(_))));
+        assert!(matches!(parse_command("use on tapestry"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_search_command() {
+        assert_eq!(parse_command("search"), Ok(Command::Search));
+        assert_eq!(parse_command("disarm dart trap"), Ok(Command::Disarm("dart trap".to_string())));
+        assert!(matches!(parse_command("disarm"), Err(ParseError::MissingArgument(_))));
     }
 
     #[test]
@@ -145,10 +854,233 @@ mod tests {
         assert_eq!(parse_command("i"), Ok(Command::Inventory));
     }
 
+    #[test]
+    fn test_parse_back_command() {
+        assert_eq!(parse_command("back"), Ok(Command::Back));
+        assert_eq!(parse_command("b"), Ok(Command::Back));
+        assert_eq!(parse_command("go back"), Ok(Command::Back));
+    }
+
+    #[test]
+    fn test_parse_named_room() {
+        assert_eq!(parse_command("enter crypt"), Ok(Command::GoNamed("crypt".to_string())));
+        assert_eq!(parse_command("go to treasure room"), Ok(Command::GoNamed("treasure room".to_string())));
+    }
+
+    #[test]
+    fn test_parse_goto_command() {
+        assert_eq!(parse_command("goto Temple Exit"), Ok(Command::GoTo("temple exit".to_string())));
+        assert!(matches!(parse_command("goto"), Err(ParseError::MissingArgument(_))));
+        assert!(matches!(parse_command("enter"), Err(ParseError::MissingArgument(_))));
+        assert!(matches!(parse_command("go to"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_bare_direction() {
+        assert_eq!(parse_command("n"), Ok(Command::Go(Direction::North)));
+        assert_eq!(parse_command("E"), Ok(Command::Go(Direction::East)));
+        assert_eq!(parse_command("south"), Ok(Command::Go(Direction::South)));
+        assert_eq!(parse_command("w"), Ok(Command::Go(Direction::West)));
+
+        // Other single-letter commands keep their meaning
+        assert_eq!(parse_command("l"), Ok(Command::Look));
+        assert_eq!(parse_command("i"), Ok(Command::Inventory));
+        assert_eq!(parse_command("h"), Ok(Command::Help));
+        assert_eq!(parse_command("q"), Ok(Command::Quit));
+    }
+
     #[test]
     fn test_parse_look_command() {
         assert_eq!(parse_command("look"), Ok(Command::Look));
         assert_eq!(parse_command("l"), Ok(Command::Look));
+        assert_eq!(parse_command("look north"), Ok(Command::LookDirection(Direction::North)));
+        assert_eq!(parse_command("look closely"), Ok(Command::Examine));
+        assert_eq!(parse_command("examine room"), Ok(Command::Examine));
+        assert_eq!(parse_command("x"), Ok(Command::Examine));
+        assert_eq!(parse_command("l w"), Ok(Command::LookDirection(Direction::West)));
+        assert_eq!(parse_command("look sideways"), Err(ParseError::UnknownDirection("sideways".to_string())));
+    }
+
+    #[test]
+    fn test_parse_throw_command() {
+        assert_eq!(parse_command("throw dagger"), Ok(Command::Throw("dagger".to_string(), None)));
+        assert_eq!(
+            parse_command("throw ceremonial dagger at guardian statue"),
+            Ok(Command::Throw("ceremonial dagger".to_string(), Some("guardian statue".to_string())))
+        );
+        assert!(matches!(parse_command("throw"), Err(ParseError::MissingArgument(_))));
+        assert!(matches!(parse_command("throw dagger at"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_exits_command() {
+        assert_eq!(parse_command("exits"), Ok(Command::Exits));
+        assert_eq!(parse_command("where"), Ok(Command::Exits));
+    }
+
+    #[test]
+    fn test_parse_map_command() {
+        assert_eq!(parse_command("map"), Ok(Command::Map));
+        assert_eq!(parse_command("m"), Ok(Command::Map));
+        assert_eq!(parse_command("map solution"), Ok(Command::SolutionMap));
+        assert!(matches!(parse_command("map everything"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_combine_command() {
+        assert_eq!(
+            parse_command("combine silver amulet with jade scarab"),
+            Ok(Command::Combine("silver amulet".to_string(), "jade scarab".to_string()))
+        );
+
+        // Missing ingredients
+        assert!(matches!(parse_command("combine torch"), Err(ParseError::MissingArgument(_))));
+        assert!(matches!(parse_command("combine torch with"), Err(ParseError::MissingArgument(_))));
+        assert!(matches!(parse_command("combine with torch"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_open_and_close_commands() {
+        assert_eq!(parse_command("open sarcophagus"), Ok(Command::Open("sarcophagus".to_string())));
+        assert_eq!(parse_command("close sarcophagus"), Ok(Command::Close("sarcophagus".to_string())));
+        assert!(matches!(parse_command("open"), Err(ParseError::MissingArgument(_))));
+        assert!(matches!(parse_command("close"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_talk_command() {
+        assert_eq!(parse_command("talk to guardian"), Ok(Command::Talk("guardian".to_string())));
+        assert_eq!(parse_command("talk guardian"), Ok(Command::Talk("guardian".to_string())));
+
+        // Missing name
+        assert!(matches!(parse_command("talk to"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_read_command() {
+        assert_eq!(parse_command("read ancient map"), Ok(Command::Read("ancient map".to_string())));
+        assert!(matches!(parse_command("read"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_hint_command() {
+        assert_eq!(parse_command("hint"), Ok(Command::Hint));
+    }
+
+    #[test]
+    fn test_parse_scores_command() {
+        assert_eq!(parse_command("scores"), Ok(Command::Scores));
+        assert_eq!(parse_command("highscores"), Ok(Command::Scores));
+        assert_eq!(parse_command("achievements"), Ok(Command::Achievements));
+        assert_eq!(parse_command("progress"), Ok(Command::Achievements));
+        assert_eq!(parse_command("shout"), Ok(Command::Shout));
+    }
+
+    #[test]
+    fn test_parse_restart_command() {
+        assert_eq!(parse_command("restart"), Ok(Command::Restart));
+        assert_eq!(parse_command("new"), Ok(Command::Restart));
+    }
+
+    #[test]
+    fn test_parse_undo_command() {
+        assert_eq!(parse_command("undo"), Ok(Command::Undo));
+    }
+
+    #[test]
+    fn test_parse_label_command() {
+        assert_eq!(parse_command("label idol room"), Ok(Command::Label("idol room".to_string())));
+        assert_eq!(parse_command("annotate dead end"), Ok(Command::Label("dead end".to_string())));
+
+        // Missing text
+        assert!(matches!(parse_command("label"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_note_commands() {
+        assert_eq!(parse_command("note idol opens the doors"), Ok(Command::Note("idol opens the doors".to_string())));
+        assert_eq!(parse_command("notes"), Ok(Command::Notes));
+        assert_eq!(parse_command("journal"), Ok(Command::Notes));
+        assert!(matches!(parse_command("note"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_give_command() {
+        assert_eq!(
+            parse_command("give gold coin to guardian"),
+            Ok(Command::Give("gold coin".to_string(), "guardian".to_string()))
+        );
+        assert_eq!(
+            parse_command("offer idol to guardian"),
+            Ok(Command::Give("idol".to_string(), "guardian".to_string()))
+        );
+        assert!(matches!(parse_command("give coin"), Err(ParseError::MissingArgument(_))));
+        assert!(matches!(parse_command("give to guardian"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_parse_retrace_command() {
+        assert_eq!(parse_command("retrace"), Ok(Command::Retrace));
+        assert_eq!(parse_command("footsteps"), Ok(Command::Retrace));
+    }
+
+    #[test]
+    fn test_parse_sorted_inventory_command() {
+        assert_eq!(parse_command("inventory sorted"), Ok(Command::SortedInventory));
+        assert_eq!(parse_command("i sorted"), Ok(Command::SortedInventory));
+        assert_eq!(parse_command("sort"), Ok(Command::SortedInventory));
+    }
+
+    #[test]
+    fn test_parse_status_command() {
+        assert_eq!(parse_command("status"), Ok(Command::Status));
+        assert_eq!(parse_command("stat"), Ok(Command::Status));
+    }
+
+    #[test]
+    fn test_parse_alias_commands() {
+        assert_eq!(
+            parse_command("alias gg = go north"),
+            Ok(Command::DefineAlias("gg".to_string(), "go north".to_string()))
+        );
+        assert_eq!(parse_command("alias gg=go north"), Ok(Command::DefineAlias("gg".to_string(), "go north".to_string())));
+        assert_eq!(parse_command("aliases"), Ok(Command::Aliases));
+        assert!(matches!(parse_command("alias gg"), Err(ParseError::MissingArgument(_))));
+        assert!(matches!(parse_command("alias = go north"), Err(ParseError::MissingArgument(_))));
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let mut aliases = HashMap::new();
+        aliases.insert("gg".to_string(), "go north".to_string());
+        aliases.insert("grab".to_string(), "take".to_string());
+        assert_eq!(expand_alias("GG", &aliases), Ok("go north".to_string()));
+        assert_eq!(expand_alias("grab torch", &aliases), Ok("take torch".to_string()));
+        assert_eq!(expand_alias("look", &aliases), Ok("look".to_string()));
+        assert_eq!(
+            parse_commands_with_aliases("gg then grab golden idol", &aliases, &VerbTable::default()),
+            vec![Ok(Command::Go(Direction::North)), Ok(Command::Take("golden idol".to_string()))]
+        );
+
+        // Aliases that lead back to themselves are stopped
+        aliases.insert("a".to_string(), "b".to_string());
+        aliases.insert("b".to_string(), "a".to_string());
+        assert_eq!(expand_alias("a", &aliases), Err(ParseError::AliasLoop("a".to_string())));
+    }
+
+    #[test]
+    fn test_parse_place_command() {
+        assert_eq!(parse_command("place golden idol"), Ok(Command::Use("golden idol".to_string())));
+        assert_eq!(
+            parse_command("place golden idol on pedestal"),
+            Ok(Command::UseOn("golden idol".to_string(), "pedestal".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_intro_command() {
+        assert_eq!(parse_command("intro"), Ok(Command::Intro));
+        assert_eq!(parse_command("story"), Ok(Command::Intro));
     }
 
     #[test]
@@ -162,6 +1094,9 @@ mod tests {
         assert_eq!(parse_command("quit"), Ok(Command::Quit));
         assert_eq!(parse_command("exit"), Ok(Command::Quit));
         assert_eq!(parse_command("q"), Ok(Command::Quit));
+        assert_eq!(parse_command("yes"), Ok(Command::Yes));
+        assert_eq!(parse_command("y"), Ok(Command::Yes));
+        assert_eq!(parse_command("no"), Ok(Command::No));
     }
 
     #[test]
@@ -169,4 +1104,77 @@ mod tests {
         assert_eq!(parse_command("jump"), Ok(Command::Unknown("jump".to_string())));
         assert_eq!(parse_command("dance"), Ok(Command::Unknown("dance".to_string())));
     }
+
+    #[test]
+    fn test_parse_misspelled_command() {
+        assert_eq!(
+            parse_command("tkae idol"),
+            Err(ParseError::UnknownCommand("tkae".to_string()))
+        );
+        assert_eq!(
+            parse_command("tkae idol").unwrap_err().to_string(),
+            "Unknown command 'tkae'. Did you mean 'take'?"
+        );
+    }
+
+    #[test]
+    fn test_parse_chained_commands() {
+        assert_eq!(
+            parse_commands("take torch then go east; use torch"),
+            vec![
+                Ok(Command::Take("torch".to_string())),
+                Ok(Command::Go(Direction::East)),
+                Ok(Command::Use("torch".to_string())),
+            ]
+        );
+
+        // A single command is parsed as before
+        assert_eq!(parse_commands("look"), vec![Ok(Command::Look)]);
+        assert_eq!(parse_commands("  "), vec![Err(ParseError::Empty)]);
+    }
+
+    #[test]
+    fn test_registered_synonym_parses() {
+        let mut verbs = VerbTable::default();
+        assert_eq!(parse_command_with("grab torch", &verbs), Ok(Command::Unknown("grab torch".to_string())));
+
+        verbs.register("grab", CommandKind::Take);
+        assert_eq!(parse_command_with("grab torch", &verbs), Ok(Command::Take("torch".to_string())));
+        assert_eq!(parse_command_with("take torch", &verbs), Ok(Command::Take("torch".to_string())));
+    }
+
+    #[test]
+    fn test_closest_verb() {
+        assert_eq!(closest_verb("quti"), Some("quit"));
+        assert_eq!(closest_verb("lok"), Some("look"));
+        assert_eq!(closest_verb("xyzzy"), None);
+    }
+
+    #[test]
+    fn test_vec_input_source_replays_in_order() {
+        let mut lines = vec!["look".to_string(), "go north".to_string()];
+        assert_eq!(lines.next_command_line(), Some("look".to_string()));
+        assert_eq!(lines.next_command_line(), Some("go north".to_string()));
+        assert_eq!(lines.next_command_line(), None);
+    }
+
+    #[test]
+    fn test_script_input_skips_blank_lines_and_comments() {
+        let mut script = ScriptInput::new("# walkthrough\n\n  take ancient map \ngo east\n");
+        assert_eq!(script.next_command_line(), Some("take ancient map".to_string()));
+        assert_eq!(script.next_command_line(), Some("go east".to_string()));
+        assert_eq!(script.next_command_line(), None);
+        assert!(ScriptInput::from_file("no/such/script.txt").is_err());
+    }
+
+    #[test]
+    fn test_script_path_follows_the_flag() {
+        let args: Vec<String> = ["game", "--no-color", SCRIPT_FLAG, "walkthrough.txt"]
+            .iter()
+            .map(|arg| arg.to_string())
+            .collect();
+        assert_eq!(script_path(&args), Some("walkthrough.txt"));
+        assert_eq!(script_path(&args[..3]), None);
+        assert_eq!(script_path(&args[..1]), None);
+    }
 }
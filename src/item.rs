@@ -0,0 +1,37 @@
+use std::collections::HashMap;
+
+/// Weight of any item without a specific entry
+pub const DEFAULT_WEIGHT: u32 = 1;
+
+/// The temple layout as drawn on the ancient map, which may not match what the player finds
+pub const ANCIENT_MAP_DIAGRAM: &str = r"The faded ink shows the temple as its builders drew it:
+
+                                    [Temple Exit]
+                                          |
+[Guardian Chamber]--[Antechamber]--[Treasure Room]
+                          |
+                   [Entrance Hall]--[Ancient Crypt]";
+
+/// Gets the weights of the temple's items, keyed by lowercase item name
+pub fn default_item_weights() -> HashMap<String, u32> {
+    [
+        ("golden idol", 5),
+        ("ceremonial dagger", 3),
+        ("torch", 2),
+        ("ancient map", 1),
+        ("silver amulet", 1),
+        ("gold amulet", 1),
+        ("jade scarab", 1),
+    ]
+    .into_iter()
+    .map(|(item, weight)| (item.to_string(), weight))
+    .collect()
+}
+
+/// Gets how many times each consumable item can be used before it is gone, keyed by lowercase item name
+pub fn default_item_uses() -> HashMap<String, u32> {
+    [("torch", 5)]
+        .into_iter()
+        .map(|(item, uses)| (item.to_string(), uses))
+        .collect()
+}
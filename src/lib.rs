@@ -0,0 +1,23 @@
+//! The game engine behind Escape the Forgotten Temple, usable from any frontend.
+
+pub mod room;
+pub mod player;
+pub mod game;
+pub mod input;
+pub mod rng;
+pub mod item;
+pub mod world;
+pub mod map;
+pub mod storage;
+pub mod score;
+pub mod crafting;
+pub mod render;
+pub mod highscores;
+pub mod events;
+pub mod messages;
+#[cfg(feature = "gui")]
+pub mod ui;
+
+pub use game::{CommandResult, Game};
+pub use input::{Command, CommandKind, InputSource, ParseError, ScriptInput, VerbTable, parse_command};
+pub use room::Direction;
@@ -1,11 +1,12 @@
-mod room;
-mod player;
-mod game;
-mod input;
-mod ui;
-
+use std::cell::RefCell;
+use std::rc::Rc;
 use druid::{AppLauncher, WindowDesc};
-use ui::{UiState, build_ui};
+use escape_forgotten_temple::Game;
+use escape_forgotten_temple::game::debug_enabled;
+use escape_forgotten_temple::storage::FileStorage;
+use escape_forgotten_temple::input::{EXIT_AFTER_SCRIPT_FLAG, ScriptInput, script_path};
+use escape_forgotten_temple::render::{Theme, terminal_width, wrap_text};
+use escape_forgotten_temple::ui::{UiState, build_ui};
 
 /// The main entry point for the game.
 fn main() {
@@ -14,8 +15,39 @@ fn main() {
         .title("Escape the Forgotten Temple")
         .window_size((800.0, 600.0));
 
-    // Create the initial game state
-    let initial_state = UiState::new();
+    // Colour terminal output unless asked not to
+    let args: Vec<String> = std::env::args().collect();
+    let theme = Theme::from_args(&args, std::env::var("NO_COLOR").ok().as_deref());
+    let width = terminal_width(std::env::var("COLUMNS").ok().as_deref());
+    let fail = |error: String| -> ! {
+        eprintln!("{}", theme.error(&wrap_text(&error, width)));
+        std::process::exit(1);
+    };
+
+    // Load a custom world if one was given on the command line
+    let script = script_path(&args);
+    let world = args.iter().skip(1).find(|arg| !arg.starts_with("--") && Some(arg.as_str()) != script);
+    let game = match world {
+        Some(path) => Game::from_world_file(path).unwrap_or_else(|error| fail(error)),
+        None => Game::new(),
+    };
+
+    // Create the initial game state, keeping high scores between sessions
+    let mut game = game
+        .with_highscores(Rc::new(RefCell::new(FileStorage::new("."))))
+        .with_debug(debug_enabled(std::env::var("TEMPLE_DEBUG").ok().as_deref()));
+
+    // Play through a script of commands before handing over to the player
+    if let Some(path) = script {
+        let mut input = ScriptInput::from_file(path).unwrap_or_else(|error| fail(error));
+        for output in game.run(&mut input) {
+            println!("{}\n", theme.render(&wrap_text(&output, width)));
+        }
+        if args.iter().any(|arg| arg == EXIT_AFTER_SCRIPT_FLAG) || game.is_game_over() {
+            return;
+        }
+    }
+    let initial_state = UiState::with_game(game);
 
     // Launch the app
     AppLauncher::with_window(main_window)
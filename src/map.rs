@@ -0,0 +1,333 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::room::{Direction, Room};
+
+/// Grid position of a room, with x growing east and y growing south
+pub type Position = (i32, i32);
+
+/// Room positions computed for the map
+#[derive(Debug, Clone)]
+pub struct Layout {
+    /// Grid position of every room that could be placed
+    pub positions: HashMap<String, Position>,
+    /// Rooms that would overlap an already placed room, with the room they overlap
+    pub collisions: Vec<(String, String)>,
+    /// Rooms only reachable through an overlapping room, with the overlapping room they lie beyond
+    pub beyond: Vec<(String, String)>,
+}
+
+/// Gets the grid offset of one step in the given direction
+fn offset(direction: &Direction) -> Position {
+    match direction {
+        Direction::North => (0, -1),
+        Direction::East => (1, 0),
+        Direction::South => (0, 1),
+        Direction::West => (-1, 0),
+    }
+}
+
+/// Computes grid positions for all rooms reachable from the start by walking their exits
+pub fn compute_layout(rooms: &HashMap<String, Room>, start: &str) -> Layout {
+    let mut positions: HashMap<String, Position> = HashMap::new();
+    let mut occupied: HashMap<Position, String> = HashMap::new();
+    let mut collisions = Vec::new();
+    let mut seen = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    positions.insert(start.to_string(), (0, 0));
+    occupied.insert((0, 0), start.to_string());
+    seen.insert(start.to_string());
+    queue.push_back(start.to_string());
+
+    while let Some(name) = queue.pop_front() {
+        let (Some(room), Some(&(x, y))) = (rooms.get(&name), positions.get(&name)) else {
+            continue;
+        };
+
+        for direction in Direction::all() {
+            let Some(target) = room.exits.get(&direction) else {
+                continue;
+            };
+            if !seen.insert(target.clone()) {
+                continue;
+            }
+
+            let (dx, dy) = offset(&direction);
+            let position = (x + dx, y + dy);
+            if let Some(other) = occupied.get(&position) {
+                collisions.push((target.clone(), other.clone()));
+                continue;
+            }
+
+            positions.insert(target.clone(), position);
+            occupied.insert(position, target.clone());
+            queue.push_back(target.clone());
+        }
+    }
+
+    // Anything past an overlapping room has nowhere to hang from
+    let mut beyond = Vec::new();
+    for (blocked, _) in &collisions {
+        let mut queue = VecDeque::from([blocked.clone()]);
+        while let Some(name) = queue.pop_front() {
+            let Some(room) = rooms.get(&name) else {
+                continue;
+            };
+            for direction in Direction::all() {
+                if let Some(target) = room.exits.get(&direction)
+                    && seen.insert(target.clone())
+                {
+                    beyond.push((target.clone(), blocked.clone()));
+                    queue.push_back(target.clone());
+                }
+            }
+        }
+    }
+
+    Layout { positions, collisions, beyond }
+}
+
+/// Computes grid positions for all rooms reachable from the start, along with the rooms that couldn't be
+/// placed: those that would overlap another room, and those only reachable through them
+pub fn layout_rooms(rooms: &HashMap<String, Room>, start: &str) -> (HashMap<String, Position>, Vec<String>) {
+    let layout = compute_layout(rooms, start);
+    let unplaced = layout.collisions.into_iter()
+        .chain(layout.beyond)
+        .map(|(room, _)| room)
+        .collect();
+    (layout.positions, unplaced)
+}
+
+/// Finds the shortest sequence of rooms leading from one room to another, including both ends
+pub fn shortest_path(rooms: &HashMap<String, Room>, from: &str, to: &str) -> Option<Vec<String>> {
+    let mut previous: HashMap<String, String> = HashMap::new();
+    let mut seen = HashSet::from([from.to_string()]);
+    let mut queue = VecDeque::from([from.to_string()]);
+
+    while let Some(name) = queue.pop_front() {
+        if name == to {
+            // Walk back from the destination to rebuild the path
+            let mut path = vec![name];
+            while let Some(prev) = previous.get(path.last()?) {
+                path.push(prev.clone());
+            }
+            path.reverse();
+            return Some(path);
+        }
+
+        let Some(room) = rooms.get(&name) else {
+            continue;
+        };
+        for direction in Direction::all() {
+            if let Some(target) = room.exits.get(&direction)
+                && seen.insert(target.clone())
+            {
+                previous.insert(target.clone(), name.clone());
+                queue.push_back(target.clone());
+            }
+        }
+    }
+
+    None
+}
+
+/// Renders the visited rooms of a layout as an ASCII grid, marking the current room with `@`,
+/// the rooms along `route` with asterisks and the `dead_ends` with an asterisk in the legend.
+/// The legend numbers the rooms in the order they appear in `visited`.
+pub fn render(
+    rooms: &HashMap<String, Room>,
+    layout: &Layout,
+    visited: &[String],
+    labels: &HashMap<String, String>,
+    route: &[String],
+    dead_ends: &[String],
+    current: &str,
+) -> String {
+    // Only draw rooms the player has been to
+    let mut shown: Vec<(&String, Position)> = layout.positions.iter()
+        .filter(|(name, _)| visited.contains(*name))
+        .map(|(name, &position)| (name, position))
+        .collect();
+    if shown.is_empty() {
+        return "You haven't explored anywhere yet.".to_string();
+    }
+    shown.sort_by_key(|&(_, (x, y))| (y, x));
+
+    // Give each room a key for the legend
+    let mut keys: HashMap<&String, char> = HashMap::new();
+    let mut legend: Vec<(usize, String)> = Vec::new();
+    let mut next_key = b'A';
+    for (name, _) in &shown {
+        let key = if name.as_str() == current {
+            '@'
+        } else {
+            let key = next_key as char;
+            next_key += 1;
+            key
+        };
+        keys.insert(name, key);
+        let step = visited.iter().position(|room| room == *name).unwrap_or_default() + 1;
+        let mut entry = format!("{}. {} - {}", step, key, name);
+        if let Some(label) = labels.get(*name) {
+            entry.push_str(&format!(" \"{}\"", label));
+        }
+        if key == '@' {
+            entry.push_str(" (you are here)");
+        }
+        if route.contains(name) || dead_ends.contains(name) {
+            entry.push_str(" *");
+        }
+        legend.push((step, entry));
+    }
+    legend.sort();
+    let legend: Vec<String> = legend.into_iter().map(|(_, entry)| entry).collect();
+
+    let by_position: HashMap<Position, &String> = shown.iter().map(|&(name, position)| (position, name)).collect();
+    let min_x = shown.iter().map(|(_, (x, _))| *x).min().unwrap_or(0);
+    let max_x = shown.iter().map(|(_, (x, _))| *x).max().unwrap_or(0);
+    let min_y = shown.iter().map(|(_, (_, y))| *y).min().unwrap_or(0);
+    let max_y = shown.iter().map(|(_, (_, y))| *y).max().unwrap_or(0);
+
+    // Checks if the room at a position has an exit leading to the room at another position
+    let linked = |from: Position, direction: Direction, to: Position| -> bool {
+        match (by_position.get(&from), by_position.get(&to)) {
+            (Some(a), Some(b)) => rooms.get(*a)
+                .and_then(|room| room.exits.get(&direction))
+                .is_some_and(|target| target == *b),
+            _ => false,
+        }
+    };
+
+    let mut lines = Vec::new();
+    for y in min_y..=max_y {
+        let mut row = String::new();
+        let mut below = String::new();
+        for x in min_x..=max_x {
+            match by_position.get(&(x, y)) {
+                Some(name) if route.contains(name) => row.push_str(&format!("*{}*", keys[name])),
+                Some(name) => row.push_str(&format!("[{}]", keys[name])),
+                None => row.push_str("   "),
+            }
+            row.push(if linked((x, y), Direction::East, (x + 1, y)) { '-' } else { ' ' });
+            below.push_str(if linked((x, y), Direction::South, (x, y + 1)) { " | " } else { "   " });
+            below.push(' ');
+        }
+        lines.push(row.trim_end().to_string());
+        if y < max_y {
+            lines.push(below.trim_end().to_string());
+        }
+    }
+
+    let mut map = format!("Map of explored rooms:\n\n{}\n\n{}", lines.join("\n"), legend.join("\n"));
+    if !route.is_empty() {
+        map.push_str("\n\nRooms marked * lie on the fastest route to the exit.");
+    } else if shown.iter().any(|(name, _)| dead_ends.contains(name)) {
+        map.push_str("\n\nRooms marked * are dead ends.");
+    }
+    for (room, other) in &layout.collisions {
+        if visited.contains(room) {
+            map.push_str(&format!("\n\nNote: {} overlaps {} on the map and is not drawn.", room, other));
+        }
+    }
+    for (room, blocked) in &layout.beyond {
+        if visited.contains(room) {
+            map.push_str(&format!("\n\nNote: {} lies beyond {} and is not drawn.", room, blocked));
+        }
+    }
+    map
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room::create_rooms;
+
+    #[test]
+    fn test_layout_follows_directions() {
+        let layout = compute_layout(&create_rooms(), "Entrance Hall");
+        assert_eq!(layout.positions["Entrance Hall"], (0, 0));
+        assert_eq!(layout.positions["Ceremonial Antechamber"], (0, -1));
+        assert_eq!(layout.positions["Ancient Crypt"], (1, 0));
+        assert_eq!(layout.positions["Temple Exit"], (1, -2));
+        assert!(layout.collisions.is_empty());
+    }
+
+    #[test]
+    fn test_shortest_path() {
+        let rooms = create_rooms();
+        assert_eq!(
+            shortest_path(&rooms, "Entrance Hall", "Temple Exit"),
+            Some(vec![
+                "Entrance Hall".to_string(),
+                "Ceremonial Antechamber".to_string(),
+                "Treasure Room".to_string(),
+                "Temple Exit".to_string(),
+            ])
+        );
+        assert_eq!(shortest_path(&rooms, "Entrance Hall", "Nowhere"), None);
+    }
+
+    #[test]
+    fn test_layout_of_a_loop_gives_every_room_its_own_cell() {
+        let mut rooms = HashMap::new();
+        for name in ["A", "B", "C", "D"] {
+            rooms.insert(name.to_string(), Room::new(name, name, false, None));
+        }
+        // A square walk that comes back to where it started
+        crate::room::link_rooms(&mut rooms, "A", Direction::East, "B");
+        crate::room::link_rooms(&mut rooms, "B", Direction::North, "C");
+        crate::room::link_rooms(&mut rooms, "C", Direction::West, "D");
+        crate::room::link_rooms(&mut rooms, "D", Direction::South, "A");
+
+        let (positions, unplaced) = layout_rooms(&rooms, "A");
+        assert!(unplaced.is_empty());
+        assert_eq!(positions.len(), 4);
+        let cells: HashSet<Position> = positions.values().copied().collect();
+        assert_eq!(cells.len(), 4);
+
+        // A loop that doesn't close up reports the room it can't place
+        rooms.get_mut("C").unwrap().add_exit(Direction::South, "E");
+        rooms.insert("E".to_string(), Room::new("E", "E", false, None));
+        let (positions, unplaced) = layout_rooms(&rooms, "A");
+        assert_eq!(unplaced, vec!["E".to_string()]);
+        assert!(!positions.contains_key("E"));
+    }
+
+    #[test]
+    fn test_layout_notes_collisions() {
+        let mut rooms = HashMap::new();
+        for name in ["A", "B", "C", "D"] {
+            rooms.insert(name.to_string(), Room::new(name, name, false, None));
+        }
+        rooms.get_mut("A").unwrap().add_exit(Direction::East, "B");
+        rooms.get_mut("B").unwrap().add_exit(Direction::North, "C");
+        // D would land on top of A
+        rooms.get_mut("B").unwrap().add_exit(Direction::West, "D");
+
+        let layout = compute_layout(&rooms, "A");
+        assert_eq!(layout.collisions, vec![("D".to_string(), "A".to_string())]);
+        assert!(!layout.positions.contains_key("D"));
+    }
+
+    #[test]
+    fn test_layout_reports_rooms_behind_a_collision() {
+        let mut rooms = HashMap::new();
+        for name in ["A", "B", "C", "D", "E", "F"] {
+            rooms.insert(name.to_string(), Room::new(name, name, false, None));
+        }
+        rooms.get_mut("A").unwrap().add_exit(Direction::East, "B");
+        rooms.get_mut("B").unwrap().add_exit(Direction::North, "C");
+        // D would land on top of A, and E and F can only be reached through it
+        rooms.get_mut("B").unwrap().add_exit(Direction::West, "D");
+        rooms.get_mut("D").unwrap().add_exit(Direction::South, "E");
+        rooms.get_mut("E").unwrap().add_exit(Direction::South, "F");
+
+        let (positions, unplaced) = layout_rooms(&rooms, "A");
+        assert_eq!(unplaced, vec!["D".to_string(), "E".to_string(), "F".to_string()]);
+        assert_eq!(positions.len(), 3);
+
+        let layout = compute_layout(&rooms, "A");
+        let visited: Vec<String> = ["A", "B", "E"].iter().map(|name| name.to_string()).collect();
+        let map = render(&rooms, &layout, &visited, &HashMap::new(), &[], &[], "A");
+        assert!(map.contains("Note: E lies beyond D and is not drawn."));
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+
+/// The text the game shows for common responses, as templates with `{name}` placeholders.
+/// Swap in a different set to translate or re-theme the game.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Messages {
+    /// Picking up an item, with `{item}`
+    pub take_item: String,
+    /// Putting down an item, with `{item}`
+    pub drop_item: String,
+    /// Asking for an item that isn't in the room, with `{item}`
+    pub no_item_here: String,
+    /// Asking for an item the player isn't carrying, with `{item}`
+    pub not_carrying: String,
+    /// Trying to take something fixed in place, with `{item}`
+    pub fixed_item: String,
+    /// Taking everything from an empty room
+    pub nothing_to_take: String,
+    /// Using an item where it does nothing, with `{item}`
+    pub cant_use_here: String,
+    /// Heading a way with no exit, with `{direction}`
+    pub cant_go: String,
+    /// Heading through a locked exit without its key, with `{direction}` and `{item}`
+    pub locked_exit: String,
+    /// Going back when the previous room isn't next door
+    pub cant_go_back: String,
+    /// Asking the player to confirm quitting
+    pub quit_prompt: String,
+    /// Saying goodbye once quitting is confirmed
+    pub quit_confirmed: String,
+    /// Going on after the player decides not to quit
+    pub quit_cancelled: String,
+    /// Answering yes when nothing was asked
+    pub nothing_to_confirm: String,
+    /// Answering no when nothing was asked
+    pub nothing_to_cancel: String,
+    /// Input that isn't a command, with `{input}`
+    pub unknown_command: String,
+    /// The list of commands shown by 'help'
+    pub help: String,
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self {
+            take_item: "You take the {item}.".to_string(),
+            drop_item: "You drop the {item}.".to_string(),
+            no_item_here: "There is no {item} here.".to_string(),
+            not_carrying: "You don't have a {item}.".to_string(),
+            fixed_item: "The {item} is far too heavy and firmly fixed in place.".to_string(),
+            nothing_to_take: "There's nothing here to take.".to_string(),
+            cant_use_here: "You can't use the {item} here.".to_string(),
+            cant_go: "You can't go {direction} from here.".to_string(),
+            locked_exit: "The way {direction} is locked. You need a {item}.".to_string(),
+            cant_go_back: "You can't go back from here.".to_string(),
+            quit_prompt: "Are you sure you want to quit? (y/n)".to_string(),
+            quit_confirmed: "Thanks for playing! Goodbye.".to_string(),
+            quit_cancelled: "Quit cancelled.".to_string(),
+            nothing_to_confirm: "There's nothing to confirm.".to_string(),
+            nothing_to_cancel: "There's nothing to cancel.".to_string(),
+            unknown_command: "I don't understand '{input}'.\nType 'help' for a list of commands.".to_string(),
+            help: HELP_TEXT.to_string(),
+        }
+    }
+}
+
+/// Fills in each `{name}` placeholder in a template with its value
+pub fn fill(template: &str, values: &[(&str, &str)]) -> String {
+    values.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+const HELP_TEXT: &str = "Available commands:\n\
+    - go [direction]: Move in the specified direction (north, east, south, west)\n\
+    - back: Return to the room you were just in\n\
+    - enter [room] / go to [room]: Move into a neighbouring room by name\n\
+    - n, e, s, w: Shortcuts for moving in a direction\n\
+    - goto [room]: Walk to a room you have visited by the shortest known way\n\
+    - take [item]: Pick up an item\n\
+    - take all: Pick up everything you can carry\n\
+    - drop [item]: Drop an item from your inventory\n\
+    - drop all: Drop everything you are carrying\n\
+    - use [item] / place [item]: Use an item from your inventory\n\
+    - use [item] on [target]: Use an item on something in the room\n\
+    - combine [item] with [item]: Combine two items you carry\n\
+    - throw [item] / throw [item] at [target]: Throw something you carry\n\
+    - look: Look around the current room\n\
+    - look [direction]: Peek into the room that way without moving\n\
+    - examine room / look closely: Study the room for details you might have missed (takes time)\n\
+    - talk to [name]: Talk to someone in the room\n\
+    - give [item] to [name]: Hand something you carry to someone in the room\n\
+    - read [thing]: Read writing in the room or on an item you carry\n\
+    - open [thing] / close [thing]: Open or close something like a sarcophagus\n\
+    - search: Search the room for hidden items (takes time)\n\
+    - disarm [trap]: Disarm a trap, if you carry the right tool\n\
+    - exits / where: List where each exit leads\n\
+    - map: Show a map of the rooms you have visited\n\
+    - map solution: Show the fastest way out (after escaping)\n\
+    - retrace / footsteps: List the directions you have taken so far\n\
+    - label [text]: Attach a note to the current room\n\
+    - note [text]: Write in your journal\n\
+    - notes / journal: Read your journal\n\
+    - alias [name] = [command]: Define a shorthand for a command\n\
+    - aliases: List your shorthands\n\
+    - inventory: Check your inventory\n\
+    - inventory sorted / sort: Check your inventory in alphabetical order\n\
+    - status / stat: Show where you are and how you are doing\n\
+    - restart: Start the game over\n\
+    - undo: Take back your last action\n\
+    - hint: Get a suggestion for what to do next\n\
+    - scores: Show the high-score table\n\
+    - achievements / progress: List the milestones you have reached\n\
+    - shout: Shout anything the game doesn't understand into the temple (say it again to stop)\n\
+    - intro / story: Read the story so far again\n\
+    - help: Display this help text\n\
+    - quit: Exit the game (asks you to confirm)";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fill_replaces_placeholders() {
+        let messages = Messages::default();
+        assert_eq!(
+            fill(&messages.locked_exit, &[("direction", "north"), ("item", "golden idol")]),
+            "The way north is locked. You need a golden idol."
+        );
+        assert_eq!(fill("No {thing} here.", &[]), "No {thing} here.");
+    }
+}
@@ -1,10 +1,31 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::item::{DEFAULT_WEIGHT, default_item_uses, default_item_weights};
+
+/// Default maximum total weight the player can carry
+pub const DEFAULT_MAX_WEIGHT: u32 = 10;
+
+/// Health the player starts with
+pub const DEFAULT_HEALTH: i32 = 100;
+
 /// Represents the player in the game
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Player {
     /// The current room where the player is located
     pub location: String,
+    /// The room the player was in before their last move
+    pub previous_location: Option<String>,
     /// Items the player has collected
     pub inventory: Vec<String>,
+    /// Weight of each item, keyed by lowercase item name
+    pub item_weights: HashMap<String, u32>,
+    /// Maximum total weight the player can carry
+    pub max_weight: u32,
+    /// Uses left in each consumable item, keyed by lowercase item name
+    #[serde(default)]
+    pub uses_remaining: HashMap<String, u32>,
+    /// How much more harm the player can take
+    pub health: i32,
 }
 
 impl Player {
@@ -12,7 +33,12 @@ impl Player {
     pub fn new(starting_location: &str) -> Self {
         Player {
             location: starting_location.to_string(),
+            previous_location: None,
             inventory: Vec::new(),
+            item_weights: default_item_weights(),
+            max_weight: DEFAULT_MAX_WEIGHT,
+            uses_remaining: default_item_uses(),
+            health: DEFAULT_HEALTH,
         }
     }
 
@@ -21,18 +47,102 @@ impl Player {
         self.inventory.push(item.to_string());
     }
 
+    /// Remove an item from the player's inventory, returning its stored name
+    pub fn drop_item(&mut self, item: &str) -> Option<String> {
+        let index = self.inventory.iter().position(|i| i.to_lowercase() == item.to_lowercase())?;
+        Some(self.inventory.remove(index))
+    }
+
+    /// Use up one charge of a consumable item, removing it once none are left.
+    /// Returns true if the item was used up; other items are unaffected.
+    pub fn use_up(&mut self, item: &str) -> bool {
+        let Some(uses) = self.uses_remaining.get_mut(&item.to_lowercase()) else {
+            return false;
+        };
+        *uses = uses.saturating_sub(1);
+        if *uses > 0 {
+            return false;
+        }
+        self.uses_remaining.remove(&item.to_lowercase());
+        self.drop_item(item).is_some()
+    }
+
+    /// Get the weight of an item, defaulting for items without a known weight
+    pub fn item_weight(&self, item: &str) -> u32 {
+        self.item_weights.get(&item.to_lowercase()).copied().unwrap_or(DEFAULT_WEIGHT)
+    }
+
+    /// Get the total weight of everything the player is carrying
+    pub fn current_weight(&self) -> u32 {
+        self.inventory.iter().map(|item| self.item_weight(item)).sum()
+    }
+
+    /// Check if the player can pick up an item without exceeding their capacity
+    pub fn can_carry(&self, item: &str) -> bool {
+        self.current_weight() + self.item_weight(item) <= self.max_weight
+    }
+
+    /// Check if the player still has health left
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
+    }
+
+    /// Check if the player is carrying more than they can handle
+    pub fn is_overloaded(&self) -> bool {
+        self.current_weight() > self.max_weight
+    }
+
     /// Check if player has the specified item
     pub fn has_item(&self, item: &str) -> bool {
         self.inventory.iter().any(|i| i.to_lowercase() == item.to_lowercase())
     }
 
-    /// Display the player's inventory
+    /// Get each distinct item carried with how many of it, in the order first picked up
+    pub fn item_counts(&self) -> Vec<(String, u32)> {
+        let mut counts: Vec<(String, u32)> = Vec::new();
+        for item in &self.inventory {
+            match counts.iter_mut().find(|(name, _)| name.eq_ignore_ascii_case(item)) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((item.clone(), 1)),
+            }
+        }
+        counts
+    }
+
+    /// Get the names of the items carried, with a count for items carried more than once
+    pub fn item_labels(&self) -> Vec<String> {
+        self.item_counts()
+            .into_iter()
+            .map(|(item, count)| if count > 1 { format!("{} x{}", item, count) } else { item })
+            .collect()
+    }
+
+    /// Get the items carried in alphabetical order, leaving the pickup order untouched
+    pub fn sorted_inventory(&self) -> Vec<String> {
+        let mut items = self.inventory.clone();
+        items.sort_by_key(|item| item.to_lowercase());
+        items
+    }
+
+    /// Display the player's inventory in the order items were picked up
     pub fn display_inventory(&self) -> String {
+        self.format_inventory(self.item_labels())
+    }
+
+    /// Display the player's inventory in alphabetical order
+    pub fn display_sorted_inventory(&self) -> String {
+        let mut labels = self.item_labels();
+        labels.sort_by_key(|label| label.to_lowercase());
+        self.format_inventory(labels)
+    }
+
+    /// Lists the given item labels under the weight carried
+    fn format_inventory(&self, labels: Vec<String>) -> String {
         if self.inventory.is_empty() {
             "Your inventory is empty.".to_string()
         } else {
-            let mut inventory_list = String::from("You are carrying:\n");
-            for item in &self.inventory {
+            let mut inventory_list = format!("You are carrying ({}/{}):\n", self.current_weight(), self.max_weight);
+            for item in labels {
                 inventory_list.push_str(&format!("- {}\n", item));
             }
             inventory_list
@@ -0,0 +1,181 @@
+/// ANSI codes for each kind of styled text
+const BOLD_CYAN: &str = "\x1b[1;36m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const RED: &str = "\x1b[31m";
+const RESET: &str = "\x1b[0m";
+
+/// Command-line flag that turns colour off
+pub const NO_COLOR_FLAG: &str = "--no-color";
+
+/// Width text is wrapped to when the terminal width is unknown
+pub const DEFAULT_WRAP_WIDTH: usize = 80;
+
+/// Picks the wrap width from the value of the COLUMNS environment variable
+pub fn terminal_width(columns_env: Option<&str>) -> usize {
+    columns_env
+        .and_then(|value| value.trim().parse().ok())
+        .filter(|&width: &usize| width > 0)
+        .unwrap_or(DEFAULT_WRAP_WIDTH)
+}
+
+/// Word-wraps each line of text to the given width, keeping existing line breaks and blank lines
+pub fn wrap_text(text: &str, width: usize) -> String {
+    let wrapped: Vec<String> = text
+        .lines()
+        .map(|line| {
+            let mut lines: Vec<String> = Vec::new();
+            let mut current = String::new();
+            for word in line.split_whitespace() {
+                // Words longer than the width get a line to themselves
+                if !current.is_empty() && current.chars().count() + 1 + word.chars().count() > width {
+                    lines.push(std::mem::take(&mut current));
+                }
+                if !current.is_empty() {
+                    current.push(' ');
+                }
+                current.push_str(word);
+            }
+            lines.push(current);
+            lines.join("\n")
+        })
+        .collect();
+    wrapped.join("\n")
+}
+
+/// Colours text printed to a terminal, or leaves it plain when disabled
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// Whether colour codes are added at all
+    enabled: bool,
+}
+
+impl Theme {
+    /// Creates a theme that colours text only if enabled
+    pub fn new(enabled: bool) -> Self {
+        Theme { enabled }
+    }
+
+    /// Picks a theme from the command-line arguments and the value of the NO_COLOR environment variable
+    pub fn from_args(args: &[String], no_color_env: Option<&str>) -> Self {
+        let flag = args.iter().any(|arg| arg == NO_COLOR_FLAG);
+        // Any non-empty NO_COLOR value turns colour off, see https://no-color.org
+        let env = no_color_env.is_some_and(|value| !value.is_empty());
+        Theme::new(!flag && !env)
+    }
+
+    /// Checks if the theme adds colour
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Styles a room name
+    pub fn room_name(&self, text: &str) -> String {
+        self.paint(BOLD_CYAN, text)
+    }
+
+    /// Styles a list of exits
+    pub fn exits(&self, text: &str) -> String {
+        self.paint(GREEN, text)
+    }
+
+    /// Styles an item
+    pub fn item(&self, text: &str) -> String {
+        self.paint(YELLOW, text)
+    }
+
+    /// Styles an error
+    pub fn error(&self, text: &str) -> String {
+        self.paint(RED, text)
+    }
+
+    /// Colours the parts of a game message: room headers, exits and listed items
+    pub fn render(&self, message: &str) -> String {
+        if !self.enabled {
+            return message.to_string();
+        }
+
+        let mut in_items = false;
+        let lines: Vec<String> = message
+            .lines()
+            .map(|line| {
+                if line.is_empty() {
+                    in_items = false;
+                    line.to_string()
+                } else if line.starts_with("[ ") && line.ends_with(" ]") {
+                    self.room_name(line)
+                } else if line.starts_with("Exits:") {
+                    self.exits(line)
+                } else if line.starts_with("You see:") || line.starts_with("You are carrying") {
+                    in_items = true;
+                    line.to_string()
+                } else if in_items && line.starts_with("- ") {
+                    format!("- {}", self.item(&line[2..]))
+                } else {
+                    line.to_string()
+                }
+            })
+            .collect();
+        lines.join("\n")
+    }
+
+    /// Wraps text in a colour code when enabled
+    fn paint(&self, code: &str, text: &str) -> String {
+        if self.enabled {
+            format!("{}{}{}", code, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_can_be_disabled() {
+        let args = vec!["game".to_string()];
+        assert!(Theme::from_args(&args, None).is_enabled());
+        assert!(Theme::from_args(&args, Some("")).is_enabled());
+        assert!(!Theme::from_args(&args, Some("1")).is_enabled());
+
+        let args = vec!["game".to_string(), NO_COLOR_FLAG.to_string()];
+        assert!(!Theme::from_args(&args, None).is_enabled());
+    }
+
+    #[test]
+    fn test_render() {
+        let message = "[ Entrance Hall ]\n\nA hall.\n\nExits: north\n\nYou see:\n- ancient map";
+
+        assert_eq!(Theme::new(false).render(message), message);
+
+        let colored = Theme::new(true).render(message);
+        assert!(colored.contains("\x1b[1;36m[ Entrance Hall ]\x1b[0m"));
+        assert!(colored.contains("\x1b[32mExits: north\x1b[0m"));
+        assert!(colored.contains("- \x1b[33mancient map\x1b[0m"));
+    }
+
+    #[test]
+    fn test_wrap_long_paragraph() {
+        let text = "The air is stale in this dark crypt. Stone sarcophagi line the walls, \
+            their carved lids depicting the deceased in repose.";
+        let wrapped = wrap_text(text, 30);
+        assert!(wrapped.lines().count() > 1);
+        assert!(wrapped.lines().all(|line| line.chars().count() <= 30));
+        assert_eq!(wrapped.split_whitespace().collect::<Vec<_>>(), text.split_whitespace().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_wrap_keeps_blank_lines() {
+        let text = "[ Entrance Hall ]\n\nA vast hall of stone.\n\nExits: north east";
+        assert_eq!(wrap_text(text, 12), "[ Entrance\nHall ]\n\nA vast hall\nof stone.\n\nExits: north\neast");
+    }
+
+    #[test]
+    fn test_terminal_width() {
+        assert_eq!(terminal_width(None), DEFAULT_WRAP_WIDTH);
+        assert_eq!(terminal_width(Some("120")), 120);
+        assert_eq!(terminal_width(Some("wide")), DEFAULT_WRAP_WIDTH);
+    }
+}
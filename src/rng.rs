@@ -0,0 +1,76 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+
+/// A small seedable pseudo-random number generator (xorshift64*)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Rng {
+    /// Current internal state, never zero
+    state: u64,
+}
+
+impl Rng {
+    /// Creates a new generator from the given seed
+    pub fn new(seed: u64) -> Self {
+        Rng {
+            state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed },
+        }
+    }
+
+    /// Creates a new generator seeded from the system clock
+    pub fn from_time() -> Self {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Rng::new(seed)
+    }
+
+    /// Returns the next pseudo-random number
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_F491_4F6C_DD1D)
+    }
+
+    /// Returns a number from 0 up to but not including the given bound
+    pub fn below(&mut self, bound: usize) -> usize {
+        (self.next_u64() % bound as u64) as usize
+    }
+
+    /// Returns true with the given percentage chance (0-100)
+    pub fn chance(&mut self, percent: u32) -> bool {
+        (self.next_u64() % 100) < percent as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_sequence() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        for _ in 0..10 {
+            assert_eq!(a.next_u64(), b.next_u64());
+        }
+    }
+
+    #[test]
+    fn test_chance_bounds() {
+        let mut rng = Rng::new(7);
+        for _ in 0..100 {
+            assert!(!rng.chance(0));
+            assert!(rng.chance(100));
+        }
+    }
+
+    #[test]
+    fn test_below_bounds() {
+        let mut rng = Rng::new(3);
+        for _ in 0..100 {
+            assert!(rng.below(5) < 5);
+        }
+    }
+}
@@ -1,7 +1,10 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use serde::{Deserialize, Serialize};
 
 /// Represents the possible directions a player can move
-#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Serialize, Deserialize)]
 pub enum Direction {
     North,
     East,
@@ -10,19 +13,18 @@ pub enum Direction {
 }
 
 impl Direction {
-    /// Converts a string to a Direction enum value
+    /// Gets every direction, in a fixed order
+    pub fn all() -> [Direction; 4] {
+        [Direction::North, Direction::East, Direction::South, Direction::West]
+    }
+
+    /// Converts a string, or its first letter, to a Direction enum value
     pub fn from_string(s: &str) -> Option<Direction> {
-        match s.to_lowercase().as_str() {
-            "north" => Some(Direction::North),
-            "east" => Some(Direction::East),
-            "south" => Some(Direction::South),
-            "west" => Some(Direction::West),
-            _ => None,
-        }
+        s.parse().ok()
     }
 
-    /// Converts a Direction enum value to a string
-    pub fn to_string(&self) -> &str {
+    /// Gets the lowercase name of the direction
+    pub fn as_str(&self) -> &'static str {
         match self {
             Direction::North => "north",
             Direction::East => "east",
@@ -30,10 +32,77 @@ impl Direction {
             Direction::West => "west",
         }
     }
+
+    /// Gets the direction pointing the opposite way
+    pub fn opposite(&self) -> Direction {
+        match self {
+            Direction::North => Direction::South,
+            Direction::East => Direction::West,
+            Direction::South => Direction::North,
+            Direction::West => Direction::East,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "north" | "n" => Ok(Direction::North),
+            "east" | "e" => Ok(Direction::East),
+            "south" | "s" => Ok(Direction::South),
+            "west" | "w" => Ok(Direction::West),
+            _ => Err(format!("Unknown direction: {}", s)),
+        }
+    }
+}
+
+/// A character the player can talk to
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Npc {
+    /// Name the player uses to address the character
+    pub name: String,
+    /// Lines the character says, in the order they are said
+    pub dialogue: Vec<String>,
+}
+
+/// Something that happens the first time the player enters a room
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomEvent {
+    /// Message shown when the event fires
+    pub message: String,
+    /// Item that appears in the room when the event fires
+    #[serde(default)]
+    pub spawn_item: Option<String>,
+}
+
+/// Something in a room that can be opened to reach the items inside
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Container {
+    /// Whether the container is currently open
+    pub open: bool,
+    /// Items inside the container
+    pub contents: Vec<String>,
+}
+
+/// How much of a room to show when describing it
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DescribeOptions {
+    /// Leave out the room's prose, for rooms the player already knows
+    pub brief: bool,
+    /// Replace the room's prose with a note that it's too dark to make out
+    pub dark: bool,
 }
 
 /// Represents a room in the game
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Room {
     /// Unique name/identifier for the room
     pub name: String,
@@ -41,12 +110,41 @@ pub struct Room {
     pub description: String,
     /// Available exits from the room
     pub exits: HashMap<Direction, String>,
+    /// Short descriptions of exits, such as "an ornate archway", by direction
+    pub exit_labels: HashMap<Direction, String>,
+    /// Directions that are permanently blocked, mapped to the message shown when trying them
+    pub blocked: HashMap<Direction, String>,
+    /// Exits that are locked, mapped to the item that unlocks them
+    pub locks: HashMap<Direction, String>,
     /// Items that can be found in the room
     pub items: Vec<String>,
+    /// Items listed in the room that can't be picked up, such as scenery
+    pub fixed_items: HashSet<String>,
+    /// Items hidden in the room until the player searches it
+    pub hidden_items: Vec<String>,
+    /// Exits that can't be seen or used until something reveals them
+    pub hidden_exits: HashSet<Direction>,
+    /// Flag indicating if searching the room can spring a trap
+    pub trapped: bool,
+    /// Health lost to a trap on entering the room: only the first time, or every time
+    /// while any of the room's `traps` is still armed
+    pub trap_damage: Option<i32>,
+    /// Traps in the room that can be disarmed, mapped to the tool needed to disarm them
+    pub traps: HashMap<String, String>,
     /// Flag indicating if this room is the winning exit
     pub is_exit: bool,
     /// Item required to win if this is an exit room
     pub required_item: Option<String>,
+    /// Characters present in the room
+    pub npcs: Vec<Npc>,
+    /// Containers in the room, by name
+    pub containers: HashMap<String, Container>,
+    /// Things in the room that can be read, mapped to what they say
+    pub readables: HashMap<String, String>,
+    /// Event that fires the first time the player enters the room
+    pub on_enter: Option<RoomEvent>,
+    /// What a close inspection of the room reveals
+    pub detail_text: Option<String>,
 }
 
 impl Room {
@@ -56,10 +154,139 @@ impl Room {
             name: name.to_string(),
             description: description.to_string(),
             exits: HashMap::new(),
+            exit_labels: HashMap::new(),
+            blocked: HashMap::new(),
+            locks: HashMap::new(),
             items: Vec::new(),
+            fixed_items: HashSet::new(),
+            hidden_items: Vec::new(),
+            hidden_exits: HashSet::new(),
+            trapped: false,
+            trap_damage: None,
+            traps: HashMap::new(),
             is_exit,
             required_item,
+            npcs: Vec::new(),
+            containers: HashMap::new(),
+            readables: HashMap::new(),
+            on_enter: None,
+            detail_text: None,
+        }
+    }
+
+    /// Creates a room with its exits and items already in place, declaring a whole room in one call
+    pub fn with_details(
+        name: &str,
+        description: &str,
+        is_exit: bool,
+        required_item: Option<String>,
+        exits: Vec<(Direction, &str)>,
+        items: Vec<&str>,
+    ) -> Self {
+        let mut room = Room::new(name, description, is_exit, required_item);
+        for (direction, target_room) in exits {
+            room.add_exit(direction, target_room);
+        }
+        for item in items {
+            room.add_item(item);
         }
+        room
+    }
+
+    /// Returns the room with an exit added, for building rooms fluently
+    pub fn with_exit(mut self, direction: Direction, target_room: &str) -> Self {
+        self.add_exit(direction, target_room);
+        self
+    }
+
+    /// Returns the room with a described exit added
+    pub fn with_labeled_exit(mut self, direction: Direction, target_room: &str, label: &str) -> Self {
+        self.add_exit(direction.clone(), target_room);
+        self.label_exit(direction, label);
+        self
+    }
+
+    /// Returns the room with an item placed in it
+    pub fn with_item(mut self, item: &str) -> Self {
+        self.add_item(item);
+        self
+    }
+
+    /// Returns the room with an item in it that can't be picked up
+    pub fn with_fixed_item(mut self, item: &str) -> Self {
+        self.add_fixed_item(item);
+        self
+    }
+
+    /// Returns the room with what a close inspection of it reveals
+    pub fn with_detail(mut self, text: &str) -> Self {
+        self.detail_text = Some(text.to_string());
+        self
+    }
+
+    /// Returns the room with the item needed to escape through it
+    pub fn with_required_item(mut self, item: &str) -> Self {
+        self.required_item = Some(item.to_string());
+        self
+    }
+
+    /// Describes the room as the player sees it: its name, prose, exits, containers, characters and items
+    pub fn describe(&self, opts: DescribeOptions) -> String {
+        let mut description = format!("[ {} ]\n", self.name);
+        if opts.dark {
+            description.push_str("\nIt's too dark to make out much of this place.\n");
+        } else if !opts.brief {
+            description.push_str(&format!("\n{}\n", self.description));
+        }
+
+        // Add exits, one per line when any of them is described
+        let exits: Vec<Direction> = Direction::all().into_iter()
+            .filter(|direction| self.visible_exit(direction).is_some())
+            .collect();
+        if exits.iter().any(|direction| self.exit_labels.contains_key(direction)) {
+            description.push_str("\nExits:");
+            for direction in &exits {
+                match self.exit_labels.get(direction) {
+                    Some(label) => description.push_str(&format!("\n- {}: {}", direction, label)),
+                    None => description.push_str(&format!("\n- {}", direction)),
+                }
+            }
+        } else if !exits.is_empty() {
+            description.push_str("\nExits:");
+            for direction in &exits {
+                description.push_str(&format!(" {}", direction));
+            }
+        }
+
+        // Add the contents of open containers
+        let mut open: Vec<_> = self.containers.iter().filter(|(_, c)| c.open).collect();
+        open.sort_by(|a, b| a.0.cmp(b.0));
+        for (name, container) in open {
+            if container.contents.is_empty() {
+                description.push_str(&format!("\n\nThe {} lies open and empty.", name));
+            } else {
+                description.push_str(&format!(
+                    "\n\nThe {} lies open. Inside: {}.",
+                    name,
+                    container.contents.join(", ")
+                ));
+            }
+        }
+
+        // Add characters
+        for npc in &self.npcs {
+            description.push_str(&format!("\n\nThe {} is here.", npc.name));
+        }
+
+        // Add items
+        if !self.items.is_empty() {
+            description.push_str("\n\nYou see:");
+            for item in &self.items {
+                description.push_str(&format!("\n- {}", item));
+            }
+        }
+
+        description
     }
 
     /// Adds an exit to the room
@@ -67,19 +294,123 @@ impl Room {
         self.exits.insert(direction, target_room.to_string());
     }
 
+    /// Describes an exit, such as "a crumbling stone staircase"
+    pub fn label_exit(&mut self, direction: Direction, label: &str) {
+        self.exit_labels.insert(direction, label.to_string());
+    }
+
+    /// Adds an exit that stays hidden until revealed
+    pub fn add_hidden_exit(&mut self, direction: Direction, target_room: &str) {
+        self.add_exit(direction.clone(), target_room);
+        self.hidden_exits.insert(direction);
+    }
+
+    /// Makes a hidden exit usable, returning false if nothing was hidden that way
+    pub fn reveal_exit(&mut self, direction: &Direction) -> bool {
+        self.hidden_exits.remove(direction)
+    }
+
+    /// Gets where an exit leads, unless it is still hidden
+    pub fn visible_exit(&self, direction: &Direction) -> Option<&String> {
+        self.exits.get(direction).filter(|_| !self.hidden_exits.contains(direction))
+    }
+
+    /// Gets the exits the player can see, with where they lead
+    pub fn visible_exits(&self) -> impl Iterator<Item = (&Direction, &String)> {
+        self.exits.iter().filter(|(direction, _)| !self.hidden_exits.contains(*direction))
+    }
+
+    /// Blocks a direction, showing the given message when the player tries to go that way
+    pub fn add_blocked_exit(&mut self, direction: Direction, message: &str) {
+        self.blocked.insert(direction, message.to_string());
+    }
+
+    /// Locks an exit so it can only be passed with the given item
+    pub fn add_lock(&mut self, direction: Direction, key_item: &str) {
+        self.locks.insert(direction, key_item.to_string());
+    }
+
     /// Adds an item to the room
     pub fn add_item(&mut self, item: &str) {
         self.items.push(item.to_string());
     }
 
-    /// Removes an item from the room
-    pub fn remove_item(&mut self, item: &str) -> bool {
+    /// Adds an item to the room that stays where it is when the player tries to take it
+    pub fn add_fixed_item(&mut self, item: &str) {
+        self.add_item(item);
+        self.fixed_items.insert(item.to_string());
+    }
+
+    /// Checks if an item is fixed in place
+    pub fn is_fixed(&self, item: &str) -> bool {
+        self.fixed_items.iter().any(|fixed| fixed.eq_ignore_ascii_case(item))
+    }
+
+    /// Hides an item in the room so it can only be found by searching
+    pub fn add_hidden_item(&mut self, item: &str) {
+        self.hidden_items.push(item.to_string());
+    }
+
+    /// Moves all hidden items into plain view, returning the ones found
+    pub fn reveal_hidden_items(&mut self) -> Vec<String> {
+        let found: Vec<String> = self.hidden_items.drain(..).collect();
+        self.items.extend(found.iter().cloned());
+        found
+    }
+
+    /// Sets a trap in the room that can be disarmed with the given tool
+    pub fn add_trap(&mut self, name: &str, tool: &str) {
+        self.traps.insert(name.to_string(), tool.to_string());
+    }
+
+    /// Adds a character to the room
+    pub fn add_npc(&mut self, name: &str, dialogue: &[&str]) {
+        self.npcs.push(Npc {
+            name: name.to_string(),
+            dialogue: dialogue.iter().map(|line| line.to_string()).collect(),
+        });
+    }
+
+    /// Adds a closed container holding the given items to the room
+    pub fn add_container(&mut self, name: &str, contents: &[&str]) {
+        self.containers.insert(name.to_string(), Container {
+            open: false,
+            contents: contents.iter().map(|item| item.to_string()).collect(),
+        });
+    }
+
+    /// Adds something readable to the room
+    pub fn add_readable(&mut self, name: &str, text: &str) {
+        self.readables.insert(name.to_string(), text.to_string());
+    }
+
+    /// Sets an event that fires the first time the player enters the room
+    pub fn set_on_enter(&mut self, message: &str, spawn_item: Option<&str>) {
+        self.on_enter = Some(RoomEvent {
+            message: message.to_string(),
+            spawn_item: spawn_item.map(|item| item.to_string()),
+        });
+    }
+
+    /// Checks if an item can be taken, either in plain view or from an open container
+    pub fn has_item(&self, item: &str) -> bool {
+        self.items.iter()
+            .chain(self.containers.values().filter(|c| c.open).flat_map(|c| c.contents.iter()))
+            .any(|i| i.to_lowercase() == item.to_lowercase())
+    }
+
+    /// Removes an item from the room, or from an open container in it, returning the name as stored
+    pub fn remove_item(&mut self, item: &str) -> Option<String> {
         if let Some(index) = self.items.iter().position(|i| i.to_lowercase() == item.to_lowercase()) {
-            self.items.remove(index);
-            true
-        } else {
-            false
+            return Some(self.items.remove(index));
+        }
+
+        for container in self.containers.values_mut().filter(|c| c.open) {
+            if let Some(index) = container.contents.iter().position(|i| i.to_lowercase() == item.to_lowercase()) {
+                return Some(container.contents.remove(index));
+            }
         }
+        None
     }
 
     /// Gets a list of available directions
@@ -87,6 +418,11 @@ impl Room {
         self.exits.keys().collect()
     }
 
+    /// Counts the ways out of the room
+    pub fn exit_count(&self) -> usize {
+        self.exits.len()
+    }
+
     /// Gets a list of available items
     pub fn available_items(&self) -> &Vec<String> {
         &self.items
@@ -97,7 +433,7 @@ impl Room {
 pub fn create_rooms() -> HashMap<String, Room> {
     let mut rooms = HashMap::new();
 
-    // Create rooms with descriptions
+    // Create rooms with descriptions and their items
     let mut entrance = Room::new(
         "Entrance Hall",
         "You stand in the grand entrance hall of the forgotten temple. \
@@ -105,18 +441,21 @@ pub fn create_rooms() -> HashMap<String, Room> {
         from cracks in the ceiling. The air is thick with the scent of ages past.",
         false,
         None,
-    );
+    )
+    .with_item("ancient map");
 
-    let mut antechamber = Room::new(
+    let mut antechamber = Room::with_details(
         "Ceremonial Antechamber",
         "This room seems to have been used for pre-ritual preparations. \
         Stone benches line the walls, and faded murals depict priests donning ceremonial garb. \
         A stone altar stands in the center, its surface stained dark from ancient offerings.",
         false,
         None,
+        Vec::new(),
+        vec!["ceremonial dagger"],
     );
 
-    let mut treasure_room = Room::new(
+    let mut treasure_room = Room::with_details(
         "Treasure Room",
         "Glinting gold and artifacts fill this small chamber. \
         Ceremonial masks, jeweled daggers, and strange artifacts cover every surface. \
@@ -124,6 +463,12 @@ pub fn create_rooms() -> HashMap<String, Room> {
         with a small inscription that reads 'Place the sacred idol to reveal the path.'",
         false,
         None,
+        Vec::new(),
+        vec!["gold coin", "gold coin"],
+    )
+    .with_detail(
+        "Up close, the pedestal's top is worn into a shallow hollow, just the size of a small idol. \
+        Fine cracks run from its base to the northern wall, as if the two were built to move together.",
     );
 
     let mut idol_chamber = Room::new(
@@ -133,7 +478,9 @@ pub fn create_rooms() -> HashMap<String, Room> {
         gleaming despite the layer of dust covering it.",
         false,
         None,
-    );
+    )
+    .with_item("golden idol")
+    .with_fixed_item("statue");
 
     let mut crypt = Room::new(
         "Ancient Crypt",
@@ -142,39 +489,70 @@ pub fn create_rooms() -> HashMap<String, Room> {
         A faded tapestry on the far wall shows a map of the stars.",
         false,
         None,
+    )
+    .with_item("torch")
+    .with_detail(
+        "Crouching low, you notice a loose flagstone near the sarcophagi. It rocks slightly underfoot, \
+        and the dust around its edges has been disturbed. Something may be hidden beneath.",
     );
 
-    let mut temple_exit = Room::new(
+    let temple_exit = Room::with_details(
         "Temple Exit",
         "Sunlight streams through a crack in the stone wall, illuminating a narrow passage. \
         This appears to be an exit from the temple, but heavy stone doors block the way. \
         There's a keyhole shaped like an idol in the center of the doors.",
         true,
-        Some(String::from("golden idol")),
+        Some("golden idol".to_string()),
+        vec![(Direction::South, "Treasure Room")],
+        Vec::new(),
     );
 
-    // Define the connections between rooms
-    entrance.add_exit(Direction::North, "Ceremonial Antechamber");
-    entrance.add_exit(Direction::East, "Ancient Crypt");
-
-    antechamber.add_exit(Direction::South, "Entrance Hall");
-    antechamber.add_exit(Direction::East, "Treasure Room");
-    antechamber.add_exit(Direction::West, "Guardian Chamber");
-
-    treasure_room.add_exit(Direction::West, "Ceremonial Antechamber");
-    treasure_room.add_exit(Direction::North, "Temple Exit");
+    // The way the player came in has caved in
+    entrance.add_blocked_exit(
+        Direction::South,
+        "The cave-in has sealed the way you came. You must find another exit.",
+    );
 
-    idol_chamber.add_exit(Direction::East, "Ceremonial Antechamber");
+    // The way to the exit only opens once the idol is placed on the pedestal
+    treasure_room.add_hidden_exit(Direction::North, "Temple Exit");
+    treasure_room.label_exit(Direction::North, "a narrow passage in the northern wall");
+    entrance.label_exit(Direction::North, "an ornate archway");
 
-    crypt.add_exit(Direction::West, "Entrance Hall");
+    // Hide items and set traps
+    crypt.add_hidden_item("silver amulet");
+    treasure_room.add_hidden_item("gold amulet");
+    crypt.trapped = true;
+    antechamber.trap_damage = Some(25);
+    antechamber.add_trap("dart trap", "ceremonial dagger");
+    crypt.add_container("sarcophagus", &["obsidian ring"]);
 
-    temple_exit.add_exit(Direction::South, "Treasure Room");
+    // Writing on the walls
+    treasure_room.set_on_enter(
+        "A flagstone sinks beneath your boot with a dull click. Somewhere above, stone grinds on stone, \
+        and a curtain of dust and pebbles pours from the ceiling before falling still. \
+        The temple knows you are here.",
+        None,
+    );
+    treasure_room.add_readable("inscription", "The inscription reads: 'Place the sacred idol to reveal the path.'");
+    crypt.add_readable(
+        "inscriptions",
+        "The inscriptions tell of the priests who sealed the temple when the ground first began to shake. \
+        One line is carved deeper than the rest: 'The stars remember the way the priests forgot.'",
+    );
+    crypt.add_readable(
+        "star map",
+        "Faded constellations are stitched across the tapestry in silver thread.",
+    );
 
-    // Place items in rooms
-    idol_chamber.add_item("golden idol");
-    crypt.add_item("torch");
-    entrance.add_item("ancient map");
-    antechamber.add_item("ceremonial dagger");
+    // The spirit of the temple's last guardian still keeps watch
+    idol_chamber.add_npc(
+        "guardian",
+        &[
+            "Few have entered this temple since the priests sealed it. Fewer still have left.",
+            "The idol was carved to open the great doors. Without it, the way north of the treasure stays shut.",
+            "Take the idol if you must, traveller. Only do not linger when the ground begins to shake.",
+        ],
+    );
 
     // Add all rooms to the HashMap
     rooms.insert(entrance.name.clone(), entrance);
@@ -184,5 +562,172 @@ pub fn create_rooms() -> HashMap<String, Room> {
     rooms.insert(crypt.name.clone(), crypt);
     rooms.insert(temple_exit.name.clone(), temple_exit);
 
+    // Define the connections between rooms
+    link_rooms(&mut rooms, "Entrance Hall", Direction::North, "Ceremonial Antechamber");
+    link_rooms(&mut rooms, "Entrance Hall", Direction::East, "Ancient Crypt");
+    link_rooms(&mut rooms, "Ceremonial Antechamber", Direction::East, "Treasure Room");
+    link_rooms(&mut rooms, "Ceremonial Antechamber", Direction::West, "Guardian Chamber");
+
     rooms
 }
+
+/// Links two rooms in both directions, so `a` leads to `b` and `b` leads back to `a`
+pub fn link_rooms(rooms: &mut HashMap<String, Room>, a: &str, dir: Direction, b: &str) {
+    if let Some(room) = rooms.get_mut(b) {
+        room.add_exit(dir.opposite(), a);
+    }
+    if let Some(room) = rooms.get_mut(a) {
+        room.add_exit(dir, b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn describe_test_room() -> Room {
+        let mut room = Room::new("Hall", "A long hall lined with pillars.", false, None)
+            .with_exit(Direction::North, "Stairs")
+            .with_item("lamp");
+        room.add_npc("keeper", &["Welcome."]);
+        room
+    }
+
+    #[test]
+    fn test_describe_full() {
+        let description = describe_test_room().describe(DescribeOptions::default());
+        assert_eq!(
+            description,
+            "[ Hall ]\n\nA long hall lined with pillars.\n\nExits: north\n\nThe keeper is here.\n\nYou see:\n- lamp"
+        );
+    }
+
+    #[test]
+    fn test_describe_brief() {
+        let description = describe_test_room().describe(DescribeOptions { brief: true, ..DescribeOptions::default() });
+        assert_eq!(description, "[ Hall ]\n\nExits: north\n\nThe keeper is here.\n\nYou see:\n- lamp");
+    }
+
+    #[test]
+    fn test_describe_dark() {
+        let description = describe_test_room().describe(DescribeOptions { dark: true, ..DescribeOptions::default() });
+        assert!(description.starts_with("[ Hall ]\n\nIt's too dark to make out much of this place.\n"));
+        assert!(!description.contains("pillars"));
+        assert!(description.contains("Exits: north"));
+        assert!(description.contains("- lamp"));
+    }
+
+    #[test]
+    fn test_describe_exits_with_and_without_labels() {
+        let brief = DescribeOptions { brief: true, ..DescribeOptions::default() };
+        let room = Room::new("Hall", "A hall.", false, None)
+            .with_exit(Direction::West, "Yard")
+            .with_exit(Direction::East, "Cellar");
+        assert_eq!(room.describe(brief), "[ Hall ]\n\nExits: east west");
+
+        let room = room.with_labeled_exit(Direction::North, "Tower", "an ornate archway");
+        assert_eq!(room.describe(brief), "[ Hall ]\n\nExits:\n- north: an ornate archway\n- east\n- west");
+    }
+
+    #[test]
+    fn test_with_details_places_exits_and_items() {
+        let room = Room::with_details(
+            "Hall",
+            "A long hall.",
+            true,
+            Some("key".to_string()),
+            vec![(Direction::North, "Tower"), (Direction::West, "Yard")],
+            vec!["lamp", "rope"],
+        );
+        assert_eq!(room.exits.len(), 2);
+        assert_eq!(room.exits.get(&Direction::North), Some(&"Tower".to_string()));
+        assert_eq!(room.exits.get(&Direction::West), Some(&"Yard".to_string()));
+        assert_eq!(room.items, vec!["lamp".to_string(), "rope".to_string()]);
+        assert!(room.is_exit);
+        assert_eq!(room.required_item, Some("key".to_string()));
+    }
+
+    #[test]
+    fn test_direction_opposite() {
+        assert_eq!(Direction::North.opposite(), Direction::South);
+        assert_eq!(Direction::East.opposite(), Direction::West);
+        assert_eq!(Direction::South.opposite(), Direction::North);
+        assert_eq!(Direction::West.opposite(), Direction::East);
+    }
+
+    #[test]
+    fn test_link_rooms() {
+        let mut rooms = HashMap::new();
+        rooms.insert("A".to_string(), Room::new("A", "Room A", false, None));
+        rooms.insert("B".to_string(), Room::new("B", "Room B", false, None));
+
+        link_rooms(&mut rooms, "A", Direction::East, "B");
+        assert_eq!(rooms["A"].exits.get(&Direction::East), Some(&"B".to_string()));
+        assert_eq!(rooms["B"].exits.get(&Direction::West), Some(&"A".to_string()));
+    }
+
+    #[test]
+    fn test_fluent_room_construction() {
+        let room = Room::new("Vault", "A sealed vault.", true, None)
+            .with_exit(Direction::South, "Hall")
+            .with_item("key")
+            .with_item("coin")
+            .with_required_item("key");
+
+        assert_eq!(room.exits.get(&Direction::South), Some(&"Hall".to_string()));
+        assert_eq!(room.items, vec!["key".to_string(), "coin".to_string()]);
+        assert_eq!(room.required_item, Some("key".to_string()));
+    }
+
+    #[test]
+    fn test_reveal_exit() {
+        let mut room = Room::new("Vault", "A sealed vault.", false, None);
+        room.add_hidden_exit(Direction::North, "Passage");
+        assert_eq!(room.visible_exit(&Direction::North), None);
+        assert_eq!(room.visible_exits().count(), 0);
+
+        assert!(room.reveal_exit(&Direction::North));
+        assert_eq!(room.visible_exit(&Direction::North), Some(&"Passage".to_string()));
+        assert!(!room.reveal_exit(&Direction::North));
+    }
+
+    #[test]
+    fn test_exit_count() {
+        let rooms = create_rooms();
+        assert_eq!(rooms["Ceremonial Antechamber"].exit_count(), 3);
+        assert_eq!(rooms["Guardian Chamber"].exit_count(), 1);
+    }
+
+    #[test]
+    fn test_every_link_has_return_path() {
+        let rooms = create_rooms();
+        for room in rooms.values().filter(|r| !r.is_exit) {
+            for (direction, target) in &room.exits {
+                let target_room = &rooms[target];
+                assert_eq!(
+                    target_room.exits.get(&direction.opposite()),
+                    Some(&room.name),
+                    "{} -> {} has no return path",
+                    room.name,
+                    target
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_remove_item_returns_stored_name() {
+        let mut room = Room::new("Test", "A test room.", false, None);
+        room.add_item("Golden Idol");
+        assert_eq!(room.remove_item("golden idol"), Some("Golden Idol".to_string()));
+        assert_eq!(room.remove_item("golden idol"), None);
+    }
+
+    #[test]
+    fn test_direction_display_and_parse() {
+        assert_eq!("north".parse::<Direction>(), Ok(Direction::North));
+        assert_eq!("W".parse::<Direction>(), Ok(Direction::West));
+        assert!("up".parse::<Direction>().is_err());
+        assert_eq!(format!("{}", Direction::East), "east");
+    }
+}
@@ -0,0 +1,40 @@
+/// Points lost for every move between rooms
+pub const MOVE_PENALTY: i32 = 1;
+
+/// Points awarded for escaping the temple through the great doors
+pub const ESCAPE_BONUS: i32 = 50;
+
+/// Points awarded for escaping the temple through the secret passage
+pub const NARROW_ESCAPE_BONUS: i32 = 20;
+
+/// Points awarded the first time each item is picked up, keyed by lowercase item name
+const ITEM_POINTS: &[(&str, i32)] = &[
+    ("golden idol", 100),
+    ("jade scarab", 40),
+    ("silver amulet", 30),
+    ("gold amulet", 30),
+    ("ceremonial dagger", 20),
+    ("ancient map", 5),
+    ("torch", 5),
+];
+
+/// Gets the points awarded for picking up an item
+pub fn item_points(item: &str) -> i32 {
+    let item = item.to_lowercase();
+    ITEM_POINTS
+        .iter()
+        .find(|(name, _)| *name == item)
+        .map(|&(_, points)| points)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_item_points() {
+        assert_eq!(item_points("Golden Idol"), 100);
+        assert_eq!(item_points("pebble"), 0);
+    }
+}
@@ -0,0 +1,107 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Directory used by the default filesystem storage
+const DEFAULT_SAVE_DIR: &str = "saves";
+
+/// A key-value store for persisted game data such as saves
+pub trait Storage {
+    /// Reads the data stored under a key, if any
+    fn read(&self, key: &str) -> Option<Vec<u8>>;
+    /// Stores data under a key, replacing anything already there
+    fn write(&mut self, key: &str, data: &[u8]) -> Result<(), String>;
+    /// Removes the data stored under a key
+    fn delete(&mut self, key: &str) -> Result<(), String>;
+}
+
+/// Storage that keeps each key in its own file inside a directory
+#[derive(Debug, Clone)]
+pub struct FileStorage {
+    /// Directory holding the stored files
+    dir: PathBuf,
+}
+
+impl FileStorage {
+    /// Creates a storage backed by the given directory
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        FileStorage { dir: dir.into() }
+    }
+
+    /// Gets the path of the file holding a key
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(key)
+    }
+}
+
+impl Default for FileStorage {
+    fn default() -> Self {
+        FileStorage::new(DEFAULT_SAVE_DIR)
+    }
+}
+
+impl Storage for FileStorage {
+    fn read(&self, key: &str) -> Option<Vec<u8>> {
+        fs::read(self.path(key)).ok()
+    }
+
+    fn write(&mut self, key: &str, data: &[u8]) -> Result<(), String> {
+        fs::create_dir_all(&self.dir)
+            .and_then(|_| fs::write(self.path(key), data))
+            .map_err(|e| format!("Failed to write '{}': {}", key, e))
+    }
+
+    fn delete(&mut self, key: &str) -> Result<(), String> {
+        match fs::remove_file(self.path(key)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to delete '{}': {}", key, e)),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Storage that keeps everything in memory, mainly for tests
+#[derive(Debug, Clone, Default)]
+pub struct MemoryStorage {
+    /// Stored data by key
+    entries: HashMap<String, Vec<u8>>,
+}
+
+impl MemoryStorage {
+    /// Creates an empty in-memory storage
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read(&self, key: &str) -> Option<Vec<u8>> {
+        self.entries.get(key).cloned()
+    }
+
+    fn write(&mut self, key: &str, data: &[u8]) -> Result<(), String> {
+        self.entries.insert(key.to_string(), data.to_vec());
+        Ok(())
+    }
+
+    fn delete(&mut self, key: &str) -> Result<(), String> {
+        self.entries.remove(key);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_storage() {
+        let mut storage = MemoryStorage::new();
+        assert_eq!(storage.read("slot"), None);
+
+        storage.write("slot", b"data").unwrap();
+        assert_eq!(storage.read("slot"), Some(b"data".to_vec()));
+
+        storage.delete("slot").unwrap();
+        assert_eq!(storage.read("slot"), None);
+    }
+}
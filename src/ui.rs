@@ -1,95 +1,430 @@
 use druid::{
-    widget::{Button, Container, Flex, Label, TextBox, CrossAxisAlignment},
-    Data, Lens, Widget, WidgetExt, Color,
+    widget::{Axis, Button, Container, Either, Flex, Label, List, Scroll, SizedBox, TextBox, CrossAxisAlignment},
+    Data, Lens, Selector, Widget, WidgetExt, Color,
     keyboard_types::Key,
-    EventCtx, Event, KeyOrValue
+    BoxConstraints, Env, EventCtx, Event, LayoutCtx, LifeCycle, LifeCycleCtx, PaintCtx,
+    Size, TimerToken, UpdateCtx,
 };
-use crate::game::Game;
+use std::sync::Arc;
+use std::time::Duration;
+use crate::game::{CommandResult, Game};
 use crate::room::Direction;
-use crate::input::{Command, parse_command};
+use crate::input::Command;
 
 // Constants for UI sizing and styling
-const WINDOW_TITLE: &str = "Escape the Forgotten Temple";
-const WINDOW_WIDTH: f64 = 800.0;
-const WINDOW_HEIGHT: f64 = 600.0;
 const PADDING: f64 = 8.0;
 const BUTTON_WIDTH: f64 = 100.0;
 const BUTTON_HEIGHT: f64 = 40.0;
+const FEEDBACK_HEIGHT: f64 = 160.0;
 
 // Custom colors for temple theme
 const TEMPLE_BACKGROUND: Color = Color::rgb8(35, 31, 32);
 const TEMPLE_TEXT: Color = Color::rgb8(255, 248, 231);
-const TEMPLE_BUTTON: Color = Color::rgb8(139, 69, 19);
-const TEMPLE_BUTTON_HOVER: Color = Color::rgb8(160, 82, 45);
+
+// Shown when the player submits an empty command
+const EMPTY_INPUT_PROMPT: &str = "Type a command, or 'help'.";
+
+// Shown after winning when high scores are kept
+const NAME_PROMPT: &str = "Enter your name for the high-score table:";
+
+// Shown across the top of the window after winning
+const ESCAPED_BANNER: &str = "You escaped the Forgotten Temple!";
+
+// Feedback log limits
+const MAX_LOG_ENTRIES: usize = 200;
+const LOG_SEPARATOR: &str = "\n\n---\n\n";
+
+// Idle hint defaults
+const IDLE_HINT_DELAY: Duration = Duration::from_secs(60);
+const IDLE_HINT_TEXT: &str = "Stuck? Try looking around or typing 'hint'.";
+
+// Sent by the item buttons, carrying the item name
+const TAKE_ITEM: Selector<String> = Selector::new("temple.take-item");
+const USE_ITEM: Selector<String> = Selector::new("temple.use-item");
+const DROP_ITEM: Selector<String> = Selector::new("temple.drop-item");
+
+/// Settings for the hint shown when the player goes quiet
+#[derive(Clone, Debug, PartialEq)]
+pub struct IdleHint {
+    /// Whether the hint is shown at all
+    pub enabled: bool,
+    /// How long the player must be idle before the hint appears
+    pub delay: Duration,
+}
+
+impl Default for IdleHint {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            delay: IDLE_HINT_DELAY,
+        }
+    }
+}
 
 #[derive(Clone, Data, Lens)]
 pub struct UiState {
     input_text: String,
-    feedback_text: String,
+    /// Every piece of feedback shown so far, oldest first
+    #[data(eq)]
+    log: Vec<String>,
+    /// Whether the player has escaped the temple
+    won: bool,
+    /// Items lying in the current room, one Take button each
+    #[data(eq)]
+    room_items: Arc<Vec<String>>,
+    /// Items the player is carrying, one row of buttons each
+    #[data(eq)]
+    inventory: Arc<Vec<String>>,
+    /// Directions with a way out of the current room, enabling their buttons
+    #[data(eq)]
+    exits: Arc<Vec<Direction>>,
     #[data(ignore)]
     game: Game,
+    #[data(ignore)]
+    command_history: Vec<String>,
+    #[data(ignore)]
+    history_index: usize,
+    #[data(ignore)]
+    idle_hint: IdleHint,
+    #[data(ignore)]
+    commands_issued: usize,
+    /// Whether the next input is the player's name for the high-score table
+    #[data(ignore)]
+    awaiting_name: bool,
+}
+
+impl Default for UiState {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl UiState {
     pub fn new() -> Self {
+        Self::with_game(Game::new())
+    }
+
+    pub fn with_game(game: Game) -> Self {
         Self {
             input_text: String::new(),
-            feedback_text: String::from("Welcome to the Forgotten Temple! Type 'help' for commands."),
-            game: Game::new(),
+            log: vec![game.welcome_message()],
+            won: false,
+            room_items: Arc::new(game.get_room_items()),
+            inventory: Arc::new(game.get_sorted_inventory()),
+            exits: Arc::new(game.get_room_exits()),
+            game,
+            command_history: Vec::new(),
+            history_index: 0,
+            idle_hint: IdleHint::default(),
+            commands_issued: 0,
+            awaiting_name: false,
+        }
+    }
+
+    pub fn with_idle_hint(mut self, idle_hint: IdleHint) -> Self {
+        self.idle_hint = idle_hint;
+        self
+    }
+
+    /// Adds feedback to the log, dropping the oldest entries past the limit
+    fn push_log(&mut self, entry: String) {
+        self.log.push(entry);
+        if self.log.len() > MAX_LOG_ENTRIES {
+            let excess = self.log.len() - MAX_LOG_ENTRIES;
+            self.log.drain(..excess);
+        }
+    }
+
+    /// Gets the whole log as displayed text
+    pub fn log_text(&self) -> String {
+        self.log.join(LOG_SEPARATOR)
+    }
+
+    /// Runs a command against the game, counting it as player activity
+    fn run_command(&mut self, command: Command) -> CommandResult {
+        self.commands_issued += 1;
+        let mut result = self.game.process_command(command);
+        // Keep the banner up until the game is restarted or the win undone
+        self.won = result.won || (self.won && result.game_over);
+        if result.won && self.game.keeps_highscores() {
+            self.awaiting_name = true;
+            result.message.push_str(&format!("\n\n{}", NAME_PROMPT));
+        }
+        self.room_items = Arc::new(self.game.get_room_items());
+        self.inventory = Arc::new(self.game.get_sorted_inventory());
+        self.exits = Arc::new(self.game.get_room_exits());
+        result.message = mark_turn(self.commands_issued, &result.message);
+        result
+    }
+
+    /// Called when the player has been idle for the configured delay
+    pub fn handle_idle_timeout(&mut self) {
+        if self.idle_hint.enabled && self.game.config().hints_enabled && !self.game.is_game_over() {
+            self.push_log(IDLE_HINT_TEXT.to_string());
         }
     }
 
     pub fn process_input(&mut self) {
-        if self.input_text.is_empty() {
+        // Nudge the player rather than showing a parser error for blank input
+        if self.input_text.trim().is_empty() {
+            self.input_text.clear();
+            self.push_log(EMPTY_INPUT_PROMPT.to_string());
             return;
         }
 
-        match parse_command(&self.input_text) {
-            Ok(cmd) => {
-                self.feedback_text = self.game.process_command(cmd);
-            }
-            Err(error) => {
-                self.feedback_text = error;
+        // The first thing typed after escaping is the player's name
+        if self.awaiting_name {
+            self.awaiting_name = false;
+            let name = self.input_text.trim().to_string();
+            self.input_text.clear();
+            let feedback = match self.game.record_score(&name) {
+                Ok(()) => format!("Score recorded for {}.\n\n{}", name, self.game.highscores_display()),
+                Err(error) => error,
+            };
+            self.push_log(feedback);
+            return;
+        }
+
+        // Remember the command and stop browsing the history
+        self.command_history.push(self.input_text.clone());
+        self.history_index = self.command_history.len();
+
+        // Run chained commands in order, stopping early if the game ends
+        let mut results = Vec::new();
+        for parsed in self.game.parse_input(&self.input_text) {
+            match parsed {
+                Ok(cmd) => {
+                    let result = self.run_command(cmd);
+                    let game_over = result.game_over;
+                    results.push(result.to_string());
+                    if game_over {
+                        break;
+                    }
+                }
+                Err(error) => {
+                    results.push(error.to_string());
+                }
             }
         }
+        self.push_log(results.join("\n\n"));
         self.input_text.clear();
     }
 
+    pub fn history_previous(&mut self) {
+        if self.history_index > 0 {
+            self.history_index -= 1;
+        }
+        if let Some(command) = self.command_history.get(self.history_index) {
+            self.input_text = command.clone();
+        }
+    }
+
+    pub fn history_next(&mut self) {
+        if self.history_index < self.command_history.len() {
+            self.history_index += 1;
+        }
+        match self.command_history.get(self.history_index) {
+            Some(command) => self.input_text = command.clone(),
+            // Past the newest entry, go back to an empty prompt
+            None => self.input_text.clear(),
+        }
+    }
+
     pub fn handle_direction(&mut self, direction: Direction) {
-        self.feedback_text = self.game.process_command(Command::Go(direction));
+        let feedback = self.run_command(Command::Go(direction)).message;
+        self.push_log(feedback);
     }
 
     pub fn handle_take(&mut self, item: String) {
-        self.feedback_text = self.game.process_command(Command::Take(item));
+        let feedback = self.run_command(Command::Take(item)).message;
+        self.push_log(feedback);
     }
 
     pub fn handle_use(&mut self, item: String) {
-        self.feedback_text = self.game.process_command(Command::Use(item));
+        let feedback = self.run_command(Command::Use(item)).message;
+        self.push_log(feedback);
+    }
+
+    pub fn handle_drop(&mut self, item: String) {
+        let feedback = self.run_command(Command::Drop(item)).message;
+        self.push_log(feedback);
     }
 
     pub fn handle_look(&mut self) {
-        self.feedback_text = self.game.process_command(Command::Look);
+        let feedback = self.run_command(Command::Look).message;
+        self.push_log(feedback);
     }
 
     pub fn handle_help(&mut self) {
-        self.feedback_text = self.game.process_command(Command::Help);
+        let feedback = self.run_command(Command::Help).message;
+        self.push_log(feedback);
     }
 }
 
+/// Prefixes feedback with the move that produced it, indenting any further lines under the marker
+fn mark_turn(turn: usize, text: &str) -> String {
+    let marker = format!("[{}] ", turn);
+    let indent = " ".repeat(marker.len());
+    text.lines()
+        .enumerate()
+        .map(|(index, line)| match index {
+            0 => format!("{}{}", marker, line),
+            _ if line.is_empty() => String::new(),
+            _ => format!("{}{}", indent, line),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 struct TextBoxController;
 
 impl<W: Widget<UiState>> druid::widget::Controller<UiState, W> for TextBoxController {
     fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &druid::Env) {
         if let Event::KeyDown(key_event) = event {
-            if key_event.key == Key::Enter {
-                data.process_input();
-                ctx.request_update();
+            match key_event.key {
+                Key::Enter => {
+                    data.process_input();
+                    ctx.request_update();
+                }
+                Key::ArrowUp => {
+                    data.history_previous();
+                    ctx.set_handled();
+                    return;
+                }
+                Key::ArrowDown => {
+                    data.history_next();
+                    ctx.set_handled();
+                    return;
+                }
+                _ => {}
             }
         }
         child.event(ctx, event, data, env)
     }
 }
 
+/// Runs the item commands sent by buttons inside the item lists
+struct ItemActionController;
+
+impl<W: Widget<UiState>> druid::widget::Controller<UiState, W> for ItemActionController {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &druid::Env) {
+        if let Event::Command(command) = event {
+            if let Some(item) = command.get(TAKE_ITEM) {
+                data.handle_take(item.clone());
+            } else if let Some(item) = command.get(USE_ITEM) {
+                data.handle_use(item.clone());
+            } else if let Some(item) = command.get(DROP_ITEM) {
+                data.handle_drop(item.clone());
+            } else {
+                return child.event(ctx, event, data, env);
+            }
+            ctx.set_handled();
+            return;
+        }
+        child.event(ctx, event, data, env)
+    }
+}
+
+/// Shows the idle hint when no command has been issued for a while
+struct IdleHintController {
+    timer: TimerToken,
+    commands_seen: usize,
+}
+
+impl IdleHintController {
+    fn new() -> Self {
+        Self {
+            timer: TimerToken::INVALID,
+            commands_seen: 0,
+        }
+    }
+
+    fn restart(&mut self, ctx: &mut EventCtx, data: &UiState) {
+        self.commands_seen = data.commands_issued;
+        self.timer = if data.idle_hint.enabled {
+            ctx.request_timer(data.idle_hint.delay)
+        } else {
+            TimerToken::INVALID
+        };
+    }
+}
+
+impl<W: Widget<UiState>> druid::widget::Controller<UiState, W> for IdleHintController {
+    fn event(&mut self, child: &mut W, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &druid::Env) {
+        match event {
+            Event::WindowConnected => self.restart(ctx, data),
+            Event::Timer(token) if *token == self.timer => {
+                data.handle_idle_timeout();
+                self.timer = TimerToken::INVALID;
+            }
+            _ => {}
+        }
+
+        child.event(ctx, event, data, env);
+
+        // Any command resets the idle clock
+        if data.commands_issued != self.commands_seen {
+            self.restart(ctx, data);
+        }
+    }
+}
+
+/// Wraps a scrolling feedback view and keeps it pinned to the newest entry
+struct AutoScroll<W> {
+    inner: Scroll<UiState, W>,
+    scroll_pending: bool,
+}
+
+impl<W: Widget<UiState>> AutoScroll<W> {
+    fn new(child: W) -> Self {
+        Self {
+            inner: Scroll::new(child).vertical(),
+            scroll_pending: true,
+        }
+    }
+}
+
+impl<W: Widget<UiState>> Widget<UiState> for AutoScroll<W> {
+    fn event(&mut self, ctx: &mut EventCtx, event: &Event, data: &mut UiState, env: &Env) {
+        self.inner.event(ctx, event, data, env)
+    }
+
+    fn lifecycle(&mut self, ctx: &mut LifeCycleCtx, event: &LifeCycle, data: &UiState, env: &Env) {
+        self.inner.lifecycle(ctx, event, data, env)
+    }
+
+    fn update(&mut self, ctx: &mut UpdateCtx, old_data: &UiState, data: &UiState, env: &Env) {
+        if old_data.log != data.log {
+            self.scroll_pending = true;
+            ctx.request_layout();
+        }
+        self.inner.update(ctx, old_data, data, env)
+    }
+
+    fn layout(&mut self, ctx: &mut LayoutCtx, bc: &BoxConstraints, data: &UiState, env: &Env) -> Size {
+        let size = self.inner.layout(ctx, bc, data, env);
+        // The new text has to be laid out before we know where the bottom is
+        if self.scroll_pending {
+            self.inner.scroll_to_on_axis(ctx, Axis::Vertical, f64::INFINITY);
+            self.scroll_pending = false;
+        }
+        size
+    }
+
+    fn paint(&mut self, ctx: &mut PaintCtx, data: &UiState, env: &Env) {
+        self.inner.paint(ctx, data, env)
+    }
+}
+
+/// A button that moves the player, greyed out when there is no exit that way
+fn direction_button(label: &'static str, direction: Direction) -> impl Widget<UiState> {
+    let target = direction.clone();
+    Button::new(label)
+        .on_click(move |_ctx, data: &mut UiState, _env| data.handle_direction(direction.clone()))
+        .disabled_if(move |data: &UiState, _| !data.exits.contains(&target))
+        .fix_size(BUTTON_WIDTH, BUTTON_HEIGHT)
+}
+
 pub fn build_ui() -> impl Widget<UiState> {
     // Room description area with temple styling
     let room_description = Label::dynamic(|data: &UiState, _| {
@@ -103,32 +438,26 @@ pub fn build_ui() -> impl Widget<UiState> {
     .background(TEMPLE_BACKGROUND)
     .rounded(8.0);
 
+    // Victory banner, shown once the player escapes
+    let banner = Either::new(
+        |data: &UiState, _| data.won,
+        Label::new(ESCAPED_BANNER)
+            .with_text_size(24.0)
+            .with_text_color(TEMPLE_TEXT)
+            .padding(PADDING),
+        SizedBox::empty(),
+    );
+
     // Direction buttons in a cross layout
     let direction_buttons = Flex::column()
-        .with_child(
-            Button::new("North")
-                .on_click(|_ctx, data: &mut UiState, _env| data.handle_direction(Direction::North))
-                .fix_size(BUTTON_WIDTH, BUTTON_HEIGHT)
-        )
+        .with_child(direction_button("North", Direction::North))
         .with_child(
             Flex::row()
-                .with_child(
-                    Button::new("West")
-                        .on_click(|_ctx, data: &mut UiState, _env| data.handle_direction(Direction::West))
-                        .fix_size(BUTTON_WIDTH, BUTTON_HEIGHT)
-                )
+                .with_child(direction_button("West", Direction::West))
                 .with_spacer(BUTTON_WIDTH)
-                .with_child(
-                    Button::new("East")
-                        .on_click(|_ctx, data: &mut UiState, _env| data.handle_direction(Direction::East))
-                        .fix_size(BUTTON_WIDTH, BUTTON_HEIGHT)
-                )
-        )
-        .with_child(
-            Button::new("South")
-                .on_click(|_ctx, data: &mut UiState, _env| data.handle_direction(Direction::South))
-                .fix_size(BUTTON_WIDTH, BUTTON_HEIGHT)
+                .with_child(direction_button("East", Direction::East))
         )
+        .with_child(direction_button("South", Direction::South))
         .cross_axis_alignment(CrossAxisAlignment::Center);
 
     // Action buttons
@@ -145,39 +474,42 @@ pub fn build_ui() -> impl Widget<UiState> {
                 .fix_size(BUTTON_WIDTH, BUTTON_HEIGHT)
         );
 
-    // Items in room buttons
+    // Items in room, each with a button to pick it up
     let room_items = Flex::row()
         .with_child(
             Label::new("Items in room:")
                 .with_text_color(TEMPLE_TEXT)
         )
         .with_flex_child(
-            Flex::row()
-                .with_child(
-                    Container::new(
-                        Label::dynamic(|data: &UiState, _| {
-                            let items = data.game.get_room_items();
-                            if items.is_empty() {
-                                "None".to_string()
-                            } else {
-                                items.join(", ")
-                            }
-                        })
+            Either::new(
+                |data: &UiState, _| data.room_items.is_empty(),
+                Container::new(
+                    Label::new("None")
                         .with_text_color(TEMPLE_TEXT)
-                    )
-                    .background(TEMPLE_BACKGROUND)
-                    .rounded(4.0)
-                    .padding(4.0)
-                ),
+                )
+                .background(TEMPLE_BACKGROUND)
+                .rounded(4.0)
+                .padding(4.0),
+                List::new(|| {
+                    Button::dynamic(|item: &String, _| format!("Take {}", item))
+                        .on_click(|ctx, item: &mut String, _env| ctx.submit_command(TAKE_ITEM.with(item.clone())))
+                        .padding(2.0)
+                })
+                .horizontal()
+                .lens(UiState::room_items),
+            ),
             1.0,
         );
 
-    // Feedback area with scrolling
+    // Feedback log with scrolling, following the newest entry
     let feedback = Container::new(
-        Label::dynamic(|data: &UiState, _| data.feedback_text.clone())
-            .with_text_size(14.0)
-            .with_text_color(TEMPLE_TEXT)
-            .with_line_break_mode(druid::widget::LineBreaking::WordWrap)
+        AutoScroll::new(
+            Label::dynamic(|data: &UiState, _| data.log_text())
+                .with_text_size(14.0)
+                .with_text_color(TEMPLE_TEXT)
+                .with_line_break_mode(druid::widget::LineBreaking::WordWrap)
+        )
+        .fix_height(FEEDBACK_HEIGHT)
     )
     .background(TEMPLE_BACKGROUND)
     .rounded(8.0)
@@ -190,12 +522,40 @@ pub fn build_ui() -> impl Widget<UiState> {
         .fix_width(400.0)
         .controller(TextBoxController);
 
-    // Inventory display
+    // Inventory, with buttons to use or drop each item
     let inventory = Container::new(
-        Label::dynamic(|data: &UiState, _| {
-            format!("Inventory: {}", data.game.get_inventory_display())
-        })
-        .with_text_color(TEMPLE_TEXT)
+        Flex::column()
+            .cross_axis_alignment(CrossAxisAlignment::Start)
+            .with_child(
+                Label::new("Inventory:")
+                    .with_text_color(TEMPLE_TEXT)
+            )
+            .with_child(
+                Either::new(
+                    |data: &UiState, _| data.inventory.is_empty(),
+                    Label::new("Empty")
+                        .with_text_color(TEMPLE_TEXT),
+                    List::new(|| {
+                        Flex::row()
+                            .with_child(
+                                Label::dynamic(|item: &String, _| item.clone())
+                                    .with_text_color(TEMPLE_TEXT)
+                            )
+                            .with_spacer(PADDING)
+                            .with_child(
+                                Button::new("Use")
+                                    .on_click(|ctx, item: &mut String, _env| ctx.submit_command(USE_ITEM.with(item.clone())))
+                            )
+                            .with_spacer(PADDING)
+                            .with_child(
+                                Button::new("Drop")
+                                    .on_click(|ctx, item: &mut String, _env| ctx.submit_command(DROP_ITEM.with(item.clone())))
+                            )
+                            .padding(2.0)
+                    })
+                    .lens(UiState::inventory),
+                )
+            )
     )
     .background(TEMPLE_BACKGROUND)
     .rounded(4.0)
@@ -205,6 +565,7 @@ pub fn build_ui() -> impl Widget<UiState> {
     Container::new(
         Flex::column()
             .cross_axis_alignment(CrossAxisAlignment::Center)
+            .with_child(banner)
             .with_child(room_description)
             .with_spacer(PADDING)
             .with_child(direction_buttons)
@@ -221,16 +582,21 @@ pub fn build_ui() -> impl Widget<UiState> {
     )
     .background(Color::rgb8(48, 43, 39))
     .padding(PADDING)
+    .controller(ItemActionController)
+    .controller(IdleHintController::new())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use crate::storage::MemoryStorage;
 
     #[test]
     fn test_ui_state_initialization() {
         let state = UiState::new();
-        assert!(state.feedback_text.contains("Welcome"));
+        assert!(state.log_text().contains("Welcome"));
         assert_eq!(state.input_text, "");
     }
 
@@ -238,7 +604,19 @@ mod tests {
     fn test_handle_direction() {
         let mut state = UiState::new();
         state.handle_direction(Direction::North);
-        assert!(state.feedback_text.contains("Ceremonial Antechamber"));
+        assert!(state.log_text().contains("Ceremonial Antechamber"));
+    }
+
+    #[test]
+    fn test_exits_follow_the_current_room() {
+        let mut state = UiState::new();
+        assert_eq!(state.exits.len(), 2);
+        assert!(state.exits.contains(&Direction::North));
+        assert!(state.exits.contains(&Direction::East));
+
+        state.handle_direction(Direction::East);
+        assert_eq!(*state.exits, state.game.get_room_exits());
+        assert!(!state.exits.contains(&Direction::East));
     }
 
     #[test]
@@ -246,14 +624,190 @@ mod tests {
         let mut state = UiState::new();
         state.input_text = "look".to_string();
         state.process_input();
-        assert!(state.feedback_text.contains("Entrance Hall"));
+        assert!(state.log_text().contains("Entrance Hall"));
+        assert_eq!(state.input_text, "");
+    }
+
+    #[test]
+    fn test_process_blank_input() {
+        let mut state = UiState::new();
+        state.input_text = "   ".to_string();
+        state.process_input();
+        assert_eq!(state.input_text, "");
+        assert_eq!(state.log.last().unwrap(), "Type a command, or 'help'.");
+        assert!(state.command_history.is_empty());
+    }
+
+    #[test]
+    fn test_process_chained_input() {
+        let mut state = UiState::new();
+        state.input_text = "go north then go west; take golden idol".to_string();
+        state.process_input();
+        assert!(state.log_text().contains("Guardian Chamber"));
+        assert!(state.log_text().contains("You take the golden idol."));
+    }
+
+    #[test]
+    fn test_command_history() {
+        let mut state = UiState::new();
+        for command in ["look", "go north", "help"] {
+            state.input_text = command.to_string();
+            state.process_input();
+        }
+        assert_eq!(state.command_history, vec!["look", "go north", "help"]);
+
+        // Browse back through the history, stopping at the oldest entry
+        state.history_previous();
+        assert_eq!(state.input_text, "help");
+        state.history_previous();
+        state.history_previous();
+        state.history_previous();
+        assert_eq!(state.input_text, "look");
+
+        // Browse forward past the newest entry to an empty prompt
+        state.history_next();
+        assert_eq!(state.input_text, "go north");
+        state.history_next();
+        state.history_next();
         assert_eq!(state.input_text, "");
     }
 
+    #[test]
+    fn test_idle_timeout_shows_hint() {
+        let mut state = UiState::new();
+        state.handle_look();
+        state.handle_idle_timeout();
+        assert!(state.log_text().contains("Entrance Hall"));
+        assert!(state.log_text().ends_with("Stuck? Try looking around or typing 'hint'."));
+    }
+
+    #[test]
+    fn test_idle_hint_can_be_disabled() {
+        let mut state = UiState::new().with_idle_hint(IdleHint { enabled: false, ..IdleHint::default() });
+        state.handle_idle_timeout();
+        assert!(!state.log_text().contains("Stuck?"));
+    }
+
+    #[test]
+    fn test_log_keeps_every_result_in_order() {
+        let mut state = UiState::new();
+        for command in ["look", "go north"] {
+            state.input_text = command.to_string();
+            state.process_input();
+        }
+        assert_eq!(state.log.len(), 3);
+        assert!(state.log[1].contains("Entrance Hall"));
+        assert!(state.log[2].contains("Ceremonial Antechamber"));
+    }
+
+    #[test]
+    fn test_log_entries_are_marked_with_their_move() {
+        let mut state = UiState::new();
+        state.handle_take("ancient map".to_string());
+        assert_eq!(state.log[1], "[1] You take the ancient map.");
+
+        // Later lines sit under the marker
+        state.handle_help();
+        assert!(state.log[2].starts_with("[2] Available commands:\n    - go [direction]"));
+    }
+
+    #[test]
+    fn test_log_is_capped() {
+        let mut state = UiState::new();
+        for _ in 0..MAX_LOG_ENTRIES + 10 {
+            state.handle_look();
+        }
+        assert_eq!(state.log.len(), MAX_LOG_ENTRIES);
+        assert!(!state.log_text().contains("Welcome"));
+    }
+
+    #[test]
+    fn test_escaping_sets_won() {
+        let mut state = UiState::new();
+        assert!(!state.won);
+
+        state.input_text = "go north then go west then take golden idol then go east then go east \
+            then place golden idol then go north then use golden idol".to_string();
+        state.process_input();
+        assert!(state.won);
+
+        state.input_text = "look".to_string();
+        state.process_input();
+        assert!(state.won);
+
+        state.input_text = "restart".to_string();
+        state.process_input();
+        assert!(!state.won);
+    }
+
+    #[test]
+    fn test_take_removes_room_item() {
+        let mut state = UiState::new();
+        assert!(state.room_items.contains(&"ancient map".to_string()));
+
+        state.handle_take("ancient map".to_string());
+        assert!(!state.room_items.contains(&"ancient map".to_string()));
+        assert!(state.log_text().contains("You take the ancient map."));
+    }
+
+    #[test]
+    fn test_drop_removes_inventory_row() {
+        let mut state = UiState::new();
+        state.handle_take("ancient map".to_string());
+        assert_eq!(*state.inventory, vec!["ancient map".to_string()]);
+
+        state.handle_drop("ancient map".to_string());
+        assert!(state.inventory.is_empty());
+        assert!(state.room_items.contains(&"ancient map".to_string()));
+    }
+
+    #[test]
+    fn test_use_golden_idol_at_exit_wins() {
+        let mut state = UiState::new();
+        state.input_text = "go north then go west then take golden idol then go east then go east \
+            then place golden idol then go north".to_string();
+        state.process_input();
+        assert!(state.inventory.contains(&"golden idol".to_string()));
+        assert!(!state.won);
+
+        state.handle_use("golden idol".to_string());
+        assert!(state.won);
+    }
+
+    #[test]
+    fn test_naming_an_item_answers_the_full_pack_prompt() {
+        let game = Game::new_with_start("Guardian Chamber", &["ceremonial dagger", "torch", "ancient map"]).unwrap();
+        let mut state = UiState::with_game(game);
+
+        state.input_text = "take golden idol".to_string();
+        state.process_input();
+        state.input_text = "map".to_string();
+        state.process_input();
+        assert!(state.log_text().contains("You drop the ancient map. You take the golden idol."));
+        assert!(state.inventory.contains(&"golden idol".to_string()));
+    }
+
+    #[test]
+    fn test_escaping_asks_for_name_when_keeping_scores() {
+        let storage = Rc::new(RefCell::new(MemoryStorage::new()));
+        let mut state = UiState::with_game(Game::new().with_highscores(storage));
+
+        state.input_text = "go north then go west then take golden idol then go east then go east \
+            then place golden idol then go north then use golden idol".to_string();
+        state.process_input();
+        assert!(state.log_text().ends_with(NAME_PROMPT));
+
+        state.input_text = "Explorer".to_string();
+        state.process_input();
+        assert!(state.log_text().contains("Score recorded for Explorer."));
+        assert!(state.log_text().contains("1. Explorer"));
+        assert_eq!(state.command_history.len(), 1);
+    }
+
     #[test]
     fn test_help_command() {
         let mut state = UiState::new();
         state.handle_help();
-        assert!(state.feedback_text.contains("Available commands"));
+        assert!(state.log_text().contains("Available commands"));
     }
 }
@@ -0,0 +1,262 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use serde::{Deserialize, Serialize};
+use crate::room::{Container, Direction, Npc, Room, RoomEvent};
+
+/// Serializable definition of a single room
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RoomDef {
+    /// Unique name/identifier for the room
+    pub name: String,
+    /// Description of the room shown to the player
+    pub description: String,
+    /// Exits from the room, keyed by direction name
+    #[serde(default)]
+    pub exits: BTreeMap<String, String>,
+    /// Short descriptions of exits, keyed by direction name
+    #[serde(default)]
+    pub exit_labels: BTreeMap<String, String>,
+    /// Blocked directions, keyed by direction name, mapped to the message shown when trying them
+    #[serde(default)]
+    pub blocked: BTreeMap<String, String>,
+    /// Locked exits, keyed by direction name, mapped to the item that unlocks them
+    #[serde(default)]
+    pub locks: BTreeMap<String, String>,
+    /// Items that can be found in the room
+    #[serde(default)]
+    pub items: Vec<String>,
+    /// Items listed in the room that can't be picked up
+    #[serde(default)]
+    pub fixed_items: Vec<String>,
+    /// Items hidden in the room until the player searches it
+    #[serde(default)]
+    pub hidden_items: Vec<String>,
+    /// Directions of exits that stay hidden until revealed
+    #[serde(default)]
+    pub hidden_exits: Vec<String>,
+    /// Flag indicating if searching the room can spring a trap
+    #[serde(default)]
+    pub trapped: bool,
+    /// Health lost to a trap the first time the room is entered
+    #[serde(default)]
+    pub trap_damage: Option<i32>,
+    /// Traps that can be disarmed, mapped to the tool needed to disarm them
+    #[serde(default)]
+    pub traps: BTreeMap<String, String>,
+    /// Flag indicating if this room is the winning exit
+    #[serde(default)]
+    pub is_exit: bool,
+    /// Item required to win if this is an exit room
+    #[serde(default)]
+    pub required_item: Option<String>,
+    /// Characters present in the room
+    #[serde(default)]
+    pub npcs: Vec<Npc>,
+    /// Containers in the room, by name
+    #[serde(default)]
+    pub containers: BTreeMap<String, Container>,
+    /// Things in the room that can be read, mapped to what they say
+    #[serde(default)]
+    pub readables: BTreeMap<String, String>,
+    /// Event that fires the first time the player enters the room
+    #[serde(default)]
+    pub on_enter: Option<RoomEvent>,
+    /// What a close inspection of the room reveals
+    #[serde(default)]
+    pub detail_text: Option<String>,
+}
+
+/// Serializable definition of a whole game world
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorldDef {
+    /// Name of the room the player starts in
+    pub start: String,
+    /// All rooms in the world
+    pub rooms: Vec<RoomDef>,
+}
+
+impl WorldDef {
+    /// Reads a world definition from a JSON file
+    pub fn from_file(path: &str) -> Result<WorldDef, String> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read world file '{}': {}", path, e))?;
+        Self::from_json(&contents)
+    }
+
+    /// Parses a world definition from a JSON string
+    pub fn from_json(json: &str) -> Result<WorldDef, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid world definition: {}", e))
+    }
+
+    /// Serializes the world definition to a JSON string
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize world: {}", e))
+    }
+
+    /// Builds a world definition from existing rooms
+    pub fn from_rooms(rooms: &HashMap<String, Room>, start: &str) -> WorldDef {
+        let mut room_defs: Vec<RoomDef> = rooms
+            .values()
+            .map(|room| RoomDef {
+                name: room.name.clone(),
+                description: room.description.clone(),
+                exits: room.exits.iter()
+                    .map(|(direction, target)| (direction.to_string(), target.clone()))
+                    .collect(),
+                exit_labels: room.exit_labels.iter()
+                    .map(|(direction, label)| (direction.to_string(), label.clone()))
+                    .collect(),
+                blocked: room.blocked.iter()
+                    .map(|(direction, message)| (direction.to_string(), message.clone()))
+                    .collect(),
+                locks: room.locks.iter()
+                    .map(|(direction, item)| (direction.to_string(), item.clone()))
+                    .collect(),
+                items: room.items.clone(),
+                fixed_items: {
+                    let mut fixed: Vec<String> = room.fixed_items.iter().cloned().collect();
+                    fixed.sort();
+                    fixed
+                },
+                hidden_items: room.hidden_items.clone(),
+                hidden_exits: Direction::all().into_iter()
+                    .filter(|direction| room.hidden_exits.contains(direction))
+                    .map(|direction| direction.to_string())
+                    .collect(),
+                trapped: room.trapped,
+                trap_damage: room.trap_damage,
+                traps: room.traps.iter()
+                    .map(|(name, tool)| (name.clone(), tool.clone()))
+                    .collect(),
+                is_exit: room.is_exit,
+                required_item: room.required_item.clone(),
+                npcs: room.npcs.clone(),
+                containers: room.containers.iter()
+                    .map(|(name, container)| (name.clone(), container.clone()))
+                    .collect(),
+                readables: room.readables.iter()
+                    .map(|(name, text)| (name.clone(), text.clone()))
+                    .collect(),
+                on_enter: room.on_enter.clone(),
+                detail_text: room.detail_text.clone(),
+            })
+            .collect();
+        room_defs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        WorldDef {
+            start: start.to_string(),
+            rooms: room_defs,
+        }
+    }
+
+    /// Converts the definition into game rooms, checking that every exit leads somewhere
+    pub fn into_rooms(self) -> Result<HashMap<String, Room>, String> {
+        let names: Vec<String> = self.rooms.iter().map(|r| r.name.clone()).collect();
+        if !names.contains(&self.start) {
+            return Err(format!("Starting room '{}' does not exist.", self.start));
+        }
+
+        let mut rooms = HashMap::new();
+        for def in self.rooms {
+            let mut room = Room::new(&def.name, &def.description, def.is_exit, def.required_item);
+
+            for (direction, target) in &def.exits {
+                let direction = parse_direction(direction, &def.name)?;
+                if !names.contains(target) {
+                    return Err(format!("Room '{}' has an exit to unknown room '{}'.", def.name, target));
+                }
+                room.add_exit(direction, target);
+            }
+            for direction in &def.hidden_exits {
+                let direction = parse_direction(direction, &def.name)?;
+                if !room.exits.contains_key(&direction) {
+                    return Err(format!("Room '{}' hides an exit {} that it doesn't have.", def.name, direction));
+                }
+                room.hidden_exits.insert(direction);
+            }
+            for (direction, label) in &def.exit_labels {
+                let direction = parse_direction(direction, &def.name)?;
+                if !room.exits.contains_key(&direction) {
+                    return Err(format!("Room '{}' describes an exit {} that it doesn't have.", def.name, direction));
+                }
+                room.label_exit(direction, label);
+            }
+            for (direction, message) in &def.blocked {
+                room.add_blocked_exit(parse_direction(direction, &def.name)?, message);
+            }
+            for (direction, item) in &def.locks {
+                room.add_lock(parse_direction(direction, &def.name)?, item);
+            }
+            for item in &def.items {
+                room.add_item(item);
+            }
+            for item in &def.fixed_items {
+                if !room.has_item(item) {
+                    return Err(format!("Room '{}' fixes an item '{}' that it doesn't have.", def.name, item));
+                }
+                room.fixed_items.insert(item.clone());
+            }
+            for item in &def.hidden_items {
+                room.add_hidden_item(item);
+            }
+            room.trapped = def.trapped;
+            room.trap_damage = def.trap_damage;
+            room.traps = def.traps.into_iter().collect();
+            room.npcs = def.npcs;
+            room.containers = def.containers.into_iter().collect();
+            room.readables = def.readables.into_iter().collect();
+            room.on_enter = def.on_enter;
+            room.detail_text = def.detail_text;
+
+            rooms.insert(room.name.clone(), room);
+        }
+
+        Ok(rooms)
+    }
+}
+
+/// Converts a direction name from a world file into a Direction
+fn parse_direction(direction: &str, room: &str) -> Result<Direction, String> {
+    direction.parse()
+        .map_err(|_| format!("Room '{}' has an invalid direction '{}'.", room, direction))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::room::create_rooms;
+
+    #[test]
+    fn test_round_trip_default_world() {
+        let world = WorldDef::from_rooms(&create_rooms(), "Entrance Hall");
+        let json = world.to_json().unwrap();
+        assert_eq!(WorldDef::from_json(&json).unwrap(), world);
+    }
+
+    #[test]
+    fn test_bundled_world_matches_default() {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/worlds/forgotten_temple.json");
+        let world = WorldDef::from_file(path).unwrap();
+        assert_eq!(world, WorldDef::from_rooms(&create_rooms(), "Entrance Hall"));
+    }
+
+    #[test]
+    fn test_invalid_direction_is_rejected() {
+        let json = r#"{
+            "start": "A",
+            "rooms": [{ "name": "A", "description": "Room A", "exits": { "up": "A" } }]
+        }"#;
+        let result = WorldDef::from_json(json).unwrap().into_rooms();
+        assert!(result.unwrap_err().contains("invalid direction"));
+    }
+
+    #[test]
+    fn test_unknown_exit_target_is_rejected() {
+        let json = r#"{
+            "start": "A",
+            "rooms": [{ "name": "A", "description": "Room A", "exits": { "north": "B" } }]
+        }"#;
+        let result = WorldDef::from_json(json).unwrap().into_rooms();
+        assert!(result.unwrap_err().contains("unknown room"));
+    }
+}
@@ -0,0 +1,105 @@
+use escape_forgotten_temple::{Game, ScriptInput};
+
+#[test]
+fn test_winning_playthrough() {
+    let mut game = Game::new();
+    let outputs = game.run_script(&[
+        "take ancient map",
+        "go east",
+        "take torch",
+        "go west",
+        "go north",
+        "go west",
+        "take golden idol",
+        "go east",
+        "go east",
+        "place golden idol",
+        "go north",
+        "use golden idol",
+    ]);
+
+    assert_eq!(outputs.len(), 12);
+    assert!(outputs.last().unwrap().contains("escaped the forgotten temple"));
+    assert!(game.is_game_over());
+}
+
+#[test]
+fn test_script_reports_parse_errors_and_stops_when_game_ends() {
+    let mut game = Game::new();
+    let outputs = game.run_script(&["dance", "quit", "yes", "look"]);
+
+    assert_eq!(outputs.len(), 3);
+    assert!(outputs[0].contains("dance"));
+    assert!(game.is_game_over());
+}
+
+#[test]
+fn test_replayed_input_runs_until_the_game_ends() {
+    let mut game = Game::new();
+    let mut input: Vec<String> = ["go north", "go west", "take golden idol", "go east", "go east", "place golden idol",
+        "go north", "use golden idol", "look"]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+    let outputs = game.run(&mut input);
+
+    assert!(game.is_game_over());
+    assert!(outputs.last().unwrap().contains("escaped the forgotten temple"));
+    // Input after the game ended is left unread
+    assert_eq!(input, vec!["look".to_string()]);
+}
+
+#[test]
+fn test_play_treats_end_of_input_as_quitting() {
+    let mut game = Game::new();
+    let mut input: Vec<String> = vec!["look".to_string()];
+    let outputs = game.play(&mut input);
+
+    assert_eq!(outputs.len(), 2);
+    assert_eq!(outputs[1], "Thanks for playing! Goodbye.");
+    assert!(game.is_game_over());
+}
+
+#[test]
+fn test_aliases_can_be_defined_used_and_listed() {
+    let mut game = Game::new();
+    let outputs = game.run_script(&["alias gg = go north", "gg", "alias gg = gg", "aliases"]);
+
+    assert_eq!(outputs[0], "'gg' now means 'go north'.");
+    assert!(outputs[1].contains("Ceremonial Antechamber"));
+    assert_eq!(outputs[2], "An alias can't stand for itself.");
+    assert_eq!(outputs[3], "Aliases:\ngg = go north");
+
+    // Aliases survive a restart
+    let outputs = game.run_script(&["restart", "gg"]);
+    assert!(outputs[1].contains("Ceremonial Antechamber"));
+}
+
+#[test]
+fn test_script_file_drives_the_game() {
+    let path = std::env::temp_dir().join(format!("temple_script_{}.txt", std::process::id()));
+    std::fs::write(&path, "# Fetch the map and torch\ntake ancient map\ngo east\n\ntake torch\n").unwrap();
+
+    let mut game = Game::new();
+    let mut input = ScriptInput::from_file(path.to_str().unwrap()).unwrap();
+    let outputs = game.run(&mut input);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(outputs.len(), 3);
+    assert_eq!(outputs[2], "You take the torch.");
+    assert!(game.get_inventory().contains(&"ancient map".to_string()));
+    assert!(game.get_inventory().contains(&"torch".to_string()));
+    assert_eq!(game.get_current_room().unwrap().name, "Ancient Crypt");
+}
+
+#[test]
+fn test_answering_no_cancels_a_prompt() {
+    let mut game = Game::new();
+    let outputs = game.run_script(&["quit", "no", "yes", "no"]);
+
+    assert_eq!(outputs[1], "Quit cancelled.");
+    // The cancelled question can't be confirmed later
+    assert_eq!(outputs[2], "There's nothing to confirm.");
+    assert_eq!(outputs[3], "There's nothing to cancel.");
+    assert!(!game.is_game_over());
+}